@@ -0,0 +1,79 @@
+//! Compares the serial `ZlibDecoder` loop with the `parallel` feature's `read_decompressed_body`.
+//!
+//! Run with `cargo bench --features parallel`.
+use std::io::{Cursor, Read, Write};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use satisfactory_sav_parser::Parser;
+
+const CHUNK_SIZE: usize = 128 * 1024;
+const BODY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Builds the compressed chunks of a synthetic body that compresses about as well as a real one.
+fn synthetic_chunks() -> Vec<u8> {
+    let mut state = 0x2545_f491_u32;
+    let body: Vec<u8> = (0..BODY_SIZE)
+        .map(|i| {
+            // mostly repeating structure with some noise, like property lists full of floats.
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            if i % 16 < 12 {
+                (i % 251) as u8
+            } else {
+                state as u8
+            }
+        })
+        .collect();
+
+    let mut save = Vec::new();
+    for chunk in body.chunks(CHUNK_SIZE) {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(chunk).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        save.extend(0x9E2A83C1u32.to_le_bytes());
+        save.extend(0x22222222u32.to_le_bytes());
+        save.extend((CHUNK_SIZE as i64).to_le_bytes());
+        save.push(3);
+        save.extend((compressed.len() as i64).to_le_bytes());
+        save.extend((chunk.len() as i64).to_le_bytes());
+        save.extend((compressed.len() as i64).to_le_bytes());
+        save.extend((chunk.len() as i64).to_le_bytes());
+        save.extend(compressed);
+    }
+    save
+}
+
+fn serial(chunks: &[u8]) -> Vec<u8> {
+    let mut parser = Parser::new(Cursor::new(chunks));
+    let mut body = Vec::new();
+    while let Some(chunk) = parser.read_compressed_body_chunk().unwrap() {
+        ZlibDecoder::new(chunk.chunk_bytes.as_slice())
+            .read_to_end(&mut body)
+            .unwrap();
+    }
+    body
+}
+
+fn parallel(chunks: &[u8]) -> Vec<u8> {
+    Parser::new(Cursor::new(chunks))
+        .read_decompressed_body()
+        .unwrap()
+}
+
+fn decompress(c: &mut Criterion) {
+    let chunks = synthetic_chunks();
+    assert_eq!(serial(&chunks), parallel(&chunks));
+
+    let mut group = c.benchmark_group("decompress");
+    group.throughput(Throughput::Bytes(BODY_SIZE as u64));
+    group.sample_size(10);
+    group.bench_function("serial", |b| b.iter(|| serial(&chunks)));
+    group.bench_function("parallel", |b| b.iter(|| parallel(&chunks)));
+    group.finish();
+}
+
+criterion_group!(benches, decompress);
+criterion_main!(benches);
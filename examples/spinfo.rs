@@ -1,11 +1,10 @@
 use std::{
     fs::File,
-    io::{BufReader, Cursor, Read as _},
+    io::{BufReader, Cursor},
 };
 
 use binrw::BinReaderExt as _;
-use flate2::read::ZlibDecoder;
-use satisfactory_sav_parser::SaveFileBody;
+use satisfactory_sav_parser::{ReadContext, SaveData};
 
 fn main() -> anyhow::Result<()> {
     let Some(ref save_path) = std::env::args().nth(1) else {
@@ -25,41 +24,7 @@ fn main() -> anyhow::Result<()> {
     let header = parser.read_header().expect("can read header");
     println!("header: {header:#?}");
 
-    let mut chunks = vec![];
-    while let Some(chunk) = parser.read_compressed_body_chunk()? {
-        chunks.push(chunk);
-    }
-
-    println!(
-        "read {} chunks totalling {} kB ({} kB uncompressed)",
-        chunks.len(),
-        chunks
-            .iter()
-            .map(|c| c.compressed_size_summary)
-            .sum::<i64>()
-            / 1024,
-        chunks
-            .iter()
-            .map(|c| c.uncompressed_size_summary)
-            .sum::<i64>()
-            / 1024,
-    );
-
-    let mut body_data_raw = vec![];
-    for chunk in &chunks {
-        let reader = Cursor::new(&chunk.chunk_bytes);
-        ZlibDecoder::new(reader)
-            .read_to_end(&mut body_data_raw)
-            .expect("can decompress body data");
-    }
-
-    assert_eq!(
-        body_data_raw.len() as i64,
-        chunks
-            .iter()
-            .map(|c| c.uncompressed_size_summary)
-            .sum::<i64>()
-    );
+    let body_data_raw = parser.read_decompressed_body()?;
     println!(
         "decompressed {} kB of body data",
         body_data_raw.len() / 1024
@@ -69,7 +34,8 @@ fn main() -> anyhow::Result<()> {
     println!("wrote decompressed data to save_dump.bin");
 
     println!("attempting decode...");
-    match Cursor::new(&body_data_raw).read_le::<SaveFileBody>() {
+    let ctx = ReadContext::new(header.save_version);
+    match Cursor::new(&body_data_raw).read_le_args::<SaveData>((&ctx,)) {
         Ok(body) => {
             dbg!(body);
         }
@@ -0,0 +1,275 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use indexmap::IndexMap;
+
+use crate::{Error, Parser, SaveFileHeader, SaveModeFlags};
+
+/// The headers of all save files in a directory, grouped by session.
+/// Only the headers are read, so building a catalog is cheap even for large saves.
+#[derive(Debug, Default)]
+pub struct SaveCatalog {
+    /// Sessions ordered by their most recent save, newest first.
+    pub sessions: Vec<CatalogSession>,
+    /// Files that looked like saves but could not be read.
+    pub errors: Vec<CatalogError>,
+}
+
+#[derive(Debug)]
+pub struct CatalogSession {
+    /// `None` for saves from before session names were stored in the header.
+    pub session_name: Option<std::string::String>,
+    /// Saves ordered by [`SaveTiming::save_date_time`](crate::SaveTiming::save_date_time), newest first.
+    pub saves: Vec<CatalogEntry>,
+}
+
+#[derive(Debug)]
+pub struct CatalogEntry {
+    pub path: PathBuf,
+    pub file_size: u64,
+    pub header: SaveFileHeader,
+}
+
+#[derive(Debug)]
+pub struct CatalogError {
+    pub path: PathBuf,
+    pub error: Error,
+}
+
+impl SaveCatalog {
+    /// Reads the header of every `.sav` file directly inside `directory`.
+    /// Files that fail to read are collected in [`errors`](SaveCatalog::errors) instead of
+    /// aborting the scan; only failing to list the directory itself is an error.
+    pub fn scan(directory: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut sessions: IndexMap<Option<std::string::String>, Vec<CatalogEntry>> =
+            IndexMap::new();
+        let mut errors = Vec::new();
+
+        let directory = directory.as_ref();
+        for dir_entry in std::fs::read_dir(directory).map_err(Error::Io)? {
+            // an entry that can't be read has no path of its own, so it is reported for the
+            // directory.
+            let path = match dir_entry {
+                Ok(dir_entry) => dir_entry.path(),
+                Err(error) => {
+                    errors.push(CatalogError {
+                        path: directory.to_owned(),
+                        error: Error::Io(error),
+                    });
+                    continue;
+                }
+            };
+            if !path.is_file()
+                || path
+                    .extension()
+                    .is_none_or(|ext| !ext.eq_ignore_ascii_case("sav"))
+            {
+                continue;
+            }
+
+            match CatalogEntry::read(&path) {
+                Ok(entry) => {
+                    let session_name = entry
+                        .header
+                        .identity
+                        .session_name
+                        .as_ref()
+                        .map(|n| n.to_string());
+                    sessions.entry(session_name).or_default().push(entry);
+                }
+                Err(error) => errors.push(CatalogError { path, error }),
+            }
+        }
+
+        let mut sessions: Vec<_> = sessions
+            .into_iter()
+            .map(|(session_name, mut saves)| {
+                saves.sort_by_key(|entry| std::cmp::Reverse(entry.header.timing.save_date_time));
+                CatalogSession {
+                    session_name,
+                    saves,
+                }
+            })
+            .collect();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.latest_save_date_time()));
+        errors.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(SaveCatalog { sessions, errors })
+    }
+
+    /// All successfully read saves, regardless of session.
+    pub fn entries(&self) -> impl Iterator<Item = &CatalogEntry> {
+        self.sessions.iter().flat_map(|session| &session.saves)
+    }
+}
+
+impl CatalogSession {
+    fn latest_save_date_time(&self) -> Option<i64> {
+        self.saves
+            .first()
+            .map(|entry| entry.header.timing.save_date_time)
+    }
+}
+
+impl CatalogEntry {
+    /// Reads only the header of the save file at `path`.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(Error::Io)?;
+        let file_size = file.metadata().map_err(Error::Io)?.len();
+        let header = Parser::new(BufReader::new(file)).read_header()?;
+
+        Ok(CatalogEntry {
+            path: path.to_path_buf(),
+            file_size,
+            header,
+        })
+    }
+
+    pub fn mode(&self) -> &SaveModeFlags {
+        &self.header.mode
+    }
+
+    /// The game build (changelist) that wrote the save.
+    pub fn build_version(&self) -> i32 {
+        self.header.build_version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use binrw::BinWrite;
+
+    use super::*;
+    use crate::{save_header_version, SaveIdentity, SaveIntegrity, SaveTiming};
+
+    /// A directory under the system temp directory that is removed again when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "satisfactory-sav-catalog-{}-{name}",
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Writes a save that only has a header. Saves without a session name use the first header
+    /// version, which predates it.
+    fn write_save(dir: &TempDir, file_name: &str, session_name: Option<&str>, save_date_time: i64) {
+        let header = SaveFileHeader {
+            save_header_version: match session_name {
+                Some(_) => save_header_version::LATEST,
+                None => save_header_version::ADDED_SESSION_ID - 1,
+            },
+            save_version: 46,
+            build_version: 365306,
+            editor_object_version: None,
+            identity: SaveIdentity {
+                map_name: "Persistent_Level".into(),
+                map_options: "".into(),
+                session_name: session_name.map(Into::into),
+                session_visibility: None,
+                save_identifier: None,
+            },
+            timing: SaveTiming {
+                save_date_time,
+                play_duration_seconds: None,
+            },
+            mode: SaveModeFlags {
+                is_modded_save: Some(true),
+                mod_metadata: None,
+                is_partitioned_world: None,
+                is_creative_mode_enabled: None,
+            },
+            integrity: SaveIntegrity { md5_hash: None },
+        };
+        let mut file = File::create(dir.0.join(file_name)).unwrap();
+        header.write_le(&mut file).unwrap();
+    }
+
+    fn file_names(saves: &[CatalogEntry]) -> Vec<&str> {
+        saves
+            .iter()
+            .map(|entry| entry.path.file_name().unwrap().to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn groups_and_sorts_sessions() {
+        let dir = TempDir::new("groups");
+        write_save(&dir, "Factory_1.sav", Some("Factory"), 100);
+        write_save(&dir, "Factory_3.sav", Some("Factory"), 300);
+        write_save(&dir, "Factory_2.SAV", Some("Factory"), 200);
+        write_save(&dir, "Island.sav", Some("Island"), 400);
+        write_save(&dir, "Old.sav", None, 50);
+
+        let catalog = SaveCatalog::scan(&dir.0).unwrap();
+        assert!(catalog.errors.is_empty());
+
+        // sessions are ordered by their newest save, saves within a session newest first.
+        let sessions: Vec<_> = catalog
+            .sessions
+            .iter()
+            .map(|session| session.session_name.as_deref())
+            .collect();
+        assert_eq!(sessions, [Some("Island"), Some("Factory"), None]);
+        assert_eq!(
+            file_names(&catalog.sessions[1].saves),
+            ["Factory_3.sav", "Factory_2.SAV", "Factory_1.sav"]
+        );
+        assert_eq!(file_names(&catalog.sessions[2].saves), ["Old.sav"]);
+
+        assert_eq!(catalog.entries().count(), 5);
+        let entry = &catalog.sessions[0].saves[0];
+        assert_eq!(entry.build_version(), 365306);
+        assert!(entry.mode().is_modded());
+        assert_eq!(
+            entry.file_size,
+            std::fs::metadata(&entry.path).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn collects_errors() {
+        let dir = TempDir::new("errors");
+        write_save(&dir, "Factory.sav", Some("Factory"), 100);
+        std::fs::write(dir.0.join("b_truncated.sav"), [13, 0]).unwrap();
+        std::fs::write(dir.0.join("a_empty.sav"), []).unwrap();
+        // files without the extension and directories are not saves.
+        std::fs::write(dir.0.join("notes.txt"), []).unwrap();
+        std::fs::create_dir(dir.0.join("backup.sav")).unwrap();
+
+        let catalog = SaveCatalog::scan(&dir.0).unwrap();
+        assert_eq!(catalog.entries().count(), 1);
+        let errors: Vec<_> = catalog
+            .errors
+            .iter()
+            .map(|error| error.path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(errors, ["a_empty.sav", "b_truncated.sav"]);
+        assert!(matches!(catalog.errors[0].error, Error::BinRead(_)));
+    }
+
+    #[test]
+    fn missing_directory() {
+        let dir = TempDir::new("missing");
+        assert!(matches!(
+            SaveCatalog::scan(dir.0.join("missing")),
+            Err(Error::Io(_))
+        ));
+    }
+}
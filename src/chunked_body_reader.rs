@@ -0,0 +1,350 @@
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    sync::{Arc, Mutex},
+};
+
+use crate::{check_decompressed_body_limit, inflate_chunk, ChunkPosition, Error, Parser};
+
+/// The default number of already-read bytes kept available for seeking backwards.
+pub const DEFAULT_SEEK_BACK_LIMIT: usize = 128 * 1024;
+
+/// Reads the decompressed save body by inflating one chunk at a time, so that the whole body never
+/// has to be held in memory.
+///
+/// Seeking forwards inflates and skips chunks as needed. Only the last `seek_back_limit` bytes
+/// before the current position are kept, which is enough for the backtracking the body decoder
+/// does. Seeking back further than that succeeds, but reading from there fails, so that restoring
+/// the position after a failed read does not hide the original error.
+///
+/// Offsets pinned through [`pins`](Self::pins) stay available for seeking back to however far the
+/// reader moves past them, until they are released.
+pub struct ChunkedBodyReader<'a, R> {
+    parser: &'a mut Parser<R>,
+    /// Decompressed bytes still held, starting at body offset `window_start`.
+    window: Vec<u8>,
+    window_start: u64,
+    position: u64,
+    seek_back_limit: usize,
+    pins: SeekBackPins,
+    /// The body offset each chunk read so far starts at.
+    chunk_starts: Vec<u64>,
+    /// The body size announced by the chunk headers read so far.
+    expected_size: i64,
+    is_finished: bool,
+}
+
+impl<'a, R> ChunkedBodyReader<'a, R>
+where
+    R: Read + Seek,
+{
+    /// Creates a reader over the chunks that follow the parser's current position, which should
+    /// be just after the header.
+    pub fn new(parser: &'a mut Parser<R>) -> Self {
+        ChunkedBodyReader {
+            parser,
+            window: Vec::new(),
+            window_start: 0,
+            position: 0,
+            seek_back_limit: DEFAULT_SEEK_BACK_LIMIT,
+            pins: SeekBackPins::default(),
+            chunk_starts: Vec::new(),
+            expected_size: 0,
+            is_finished: false,
+        }
+    }
+
+    pub fn with_seek_back_limit(mut self, seek_back_limit: usize) -> Self {
+        self.seek_back_limit = seek_back_limit;
+        self
+    }
+
+    /// A handle for pinning offsets of this reader, usually passed on with
+    /// [`ReadContext::with_seek_back_pins`](crate::ReadContext::with_seek_back_pins).
+    pub fn pins(&self) -> SeekBackPins {
+        self.pins.clone()
+    }
+
+    /// Finds the chunk containing a body offset, among the chunks read so far.
+    pub fn chunk_position(&self, offset: u64) -> Option<ChunkPosition> {
+        let index = self
+            .chunk_starts
+            .partition_point(|&start| start <= offset)
+            .checked_sub(1)?;
+        Some(ChunkPosition {
+            index,
+            offset: offset - self.chunk_starts[index],
+        })
+    }
+
+    fn window_end(&self) -> u64 {
+        self.window_start + self.window.len() as u64
+    }
+
+    /// Inflates the next chunk into the window, returning `false` once there are no more chunks.
+    fn fill(&mut self) -> io::Result<bool> {
+        if self.is_finished {
+            return Ok(false);
+        }
+
+        let Some(chunk) = self
+            .parser
+            .read_compressed_body_chunk()
+            .map_err(to_io_error)?
+        else {
+            self.is_finished = true;
+            let actual_size = self.window_end() as i64;
+            if actual_size != self.expected_size {
+                return Err(to_io_error(Error::SizeMismatch {
+                    what: "decompressed body",
+                    expected: self.expected_size,
+                    actual: actual_size,
+                }));
+            }
+            return Ok(false);
+        };
+        check_decompressed_body_limit(self.parser.limits(), self.window_end(), &chunk)
+            .map_err(to_io_error)?;
+        self.chunk_starts.push(self.window_end());
+        inflate_chunk(&chunk, self.chunk_starts.len() - 1, &mut self.window)
+            .map_err(to_io_error)?;
+        self.expected_size += chunk.uncompressed_size_summary;
+
+        // forget everything that is further behind the current position than we allow seeking,
+        // unless it is pinned.
+        let mut keep_from = self.position.saturating_sub(self.seek_back_limit as u64);
+        if let Some(lowest) = self.pins.lowest() {
+            keep_from = keep_from.min(lowest);
+        }
+        let keep_from = keep_from.clamp(self.window_start, self.window_end());
+        self.window
+            .drain(..(keep_from - self.window_start) as usize);
+        self.window_start = keep_from;
+
+        Ok(true)
+    }
+}
+
+impl<R> Read for ChunkedBodyReader<'_, R>
+where
+    R: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position < self.window_start {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "cannot read at {} after seeking back, only {} bytes before the furthest read position are kept",
+                    self.position, self.seek_back_limit
+                ),
+            ));
+        }
+
+        while self.position >= self.window_end() {
+            if !self.fill()? {
+                return Ok(0);
+            }
+        }
+
+        let start = (self.position - self.window_start) as usize;
+        let available = &self.window[start..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<R> Seek for ChunkedBodyReader<'_, R>
+where
+    R: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "cannot seek from the end of a chunked save body",
+                ))
+            }
+        };
+        let Some(target) = target else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot seek before the start of the save body",
+            ));
+        };
+
+        // make sure the target is still covered by the window after skipping ahead.
+        self.position = target;
+        while target > self.window_end() && self.fill()? {}
+
+        Ok(self.position)
+    }
+}
+
+/// Body offsets that a [`ChunkedBodyReader`] keeps available for seeking back to, shared between
+/// the reader and whatever reads from it.
+///
+/// A handle that is not connected to a reader can still be pinned, it just has no effect.
+#[derive(Debug, Clone, Default)]
+pub struct SeekBackPins(Arc<Mutex<Vec<u64>>>);
+
+impl SeekBackPins {
+    /// Keeps `offset` and everything after it available until it is released.
+    /// An offset the reader has not reached yet starts being kept once the reader passes it.
+    pub fn pin(&self, offset: u64) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(offset);
+    }
+
+    /// Releases one pin of `offset`.
+    pub fn release(&self, offset: u64) {
+        let mut pins = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(index) = pins.iter().rposition(|&pin| pin == offset) {
+            pins.swap_remove(index);
+        }
+    }
+
+    fn lowest(&self) -> Option<u64> {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .copied()
+            .min()
+    }
+}
+
+fn to_io_error(error: Error) -> io::Error {
+    match error {
+        Error::BinRead(binrw::Error::Io(error)) => error,
+        error => io::Error::new(io::ErrorKind::InvalidData, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use binrw::{BinRead, Endian};
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use super::*;
+    use crate::{PropertyList, PropertyValue, ReadContext, SizeValidation};
+
+    const CHUNK_SIZE: usize = 128 * 1024;
+
+    /// Compresses `body` into chunks the way the game does.
+    fn chunks(body: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for part in body.chunks(CHUNK_SIZE) {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
+            encoder.write_all(part).unwrap();
+            let compressed = encoder.finish().unwrap();
+
+            data.extend(0x9E2A83C1u32.to_le_bytes());
+            data.extend(0x22222222u32.to_le_bytes());
+            data.extend((CHUNK_SIZE as i64).to_le_bytes());
+            data.push(3);
+            for size in [compressed.len(), part.len(), compressed.len(), part.len()] {
+                data.extend((size as i64).to_le_bytes());
+            }
+            data.extend(compressed);
+        }
+        data
+    }
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as i32 + 1).to_le_bytes().to_vec();
+        data.extend(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    /// A property list with one string array property that is larger than the seek-back window
+    /// and whose last element is missing its terminator.
+    fn broken_array_property() -> (Vec<u8>, usize) {
+        let count = 40_000i32;
+        let mut value = (count + 1).to_le_bytes().to_vec();
+        for _ in 0..count {
+            value.extend(string("element"));
+        }
+        value.extend(3i32.to_le_bytes());
+        value.extend(b"abc");
+
+        let mut body = string("mBig");
+        body.extend(string("ArrayProperty"));
+        body.extend((value.len() as i32).to_le_bytes());
+        body.extend(0i32.to_le_bytes());
+        body.extend(string("StrProperty"));
+        body.push(0);
+        body.extend(&value);
+        body.extend(string("None"));
+        (body, value.len())
+    }
+
+    #[test]
+    fn raw_property_larger_than_seek_back_window() {
+        let (body, size) = broken_array_property();
+        assert!(size > 3 * CHUNK_SIZE);
+        let mut parser = Parser::new(Cursor::new(chunks(&body)));
+        let mut reader = parser.body_reader();
+        let ctx = ReadContext::new(46)
+            .with_lenient(true)
+            .with_seek_back_pins(reader.pins());
+
+        let properties =
+            PropertyList::read_options(&mut reader, Endian::Little, (None, &ctx)).unwrap();
+
+        let PropertyValue::Raw(raw) = &properties.0[0].value else {
+            panic!("expected a raw property");
+        };
+        assert_eq!(raw.prop_type, "ArrayProperty");
+        assert_eq!(raw.bytes.len(), size);
+        assert_eq!(ctx.take_warnings().len(), 1);
+    }
+
+    #[test]
+    fn unpinned_seek_back_past_window_fails() {
+        let (body, _) = broken_array_property();
+        let mut parser = Parser::new(Cursor::new(chunks(&body)));
+        let mut reader = parser.body_reader();
+        let ctx = ReadContext::new(46).with_lenient(true);
+
+        let Err(error) = PropertyList::read_options(&mut reader, Endian::Little, (None, &ctx))
+        else {
+            panic!("expected the seek back to fail");
+        };
+
+        assert!(
+            error.to_string().contains("after seeking back"),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn resync_seeks_back_past_window_when_pinned() {
+        let body: Vec<u8> = (0..4 * CHUNK_SIZE).map(|i| i as u8).collect();
+        let mut parser = Parser::new(Cursor::new(chunks(&body)));
+        let mut reader = parser.body_reader();
+        let ctx = ReadContext::default()
+            .with_size_validation(SizeValidation::Resync)
+            .with_seek_back_pins(reader.pins());
+
+        ctx.pinned_for_resync(100, || {
+            reader
+                .seek(SeekFrom::Start(3 * CHUNK_SIZE as u64 + 1))
+                .unwrap();
+            ctx.check_size(&mut reader, "test", 0, 100).unwrap();
+        });
+
+        let mut byte = [0];
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(byte, [100]);
+        assert_eq!(ctx.take_warnings().len(), 1);
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    io::{Read, Seek, SeekFrom},
+};
+
+use binrw::{BinRead, BinResult, Endian, VecArgs};
+
+use crate::{
+    chunked_body_reader::SeekBackPins,
+    limits::{self, ReadLimits},
+    save_version,
+    types::string::StringDecoding,
+    Error,
+};
+
+/// State shared by everything read from a single save body.
+/// A reference to the context is passed down through the binrw arguments of the body's types.
+#[derive(Debug, Default)]
+pub struct ReadContext {
+    /// The [`SaveFileHeader::save_version`](crate::SaveFileHeader::save_version) of the save.
+    pub save_version: i32,
+    /// How strictly strings are checked, see
+    /// [`with_string_decoding`](crate::types::string::with_string_decoding).
+    pub string_decoding: StringDecoding,
+    /// Whether properties that fail to decode are kept as
+    /// [`RawProperty`](crate::RawProperty) with a warning instead of failing the whole save.
+    pub lenient: bool,
+    /// Bounds on declared sizes and nesting, see
+    /// [`with_read_limits`](crate::limits::with_read_limits).
+    pub limits: ReadLimits,
+    pub size_validation: SizeValidation,
+    /// Offsets that lenient decoding and resynchronising may seek back to are pinned here, so a
+    /// [`ChunkedBodyReader`](crate::ChunkedBodyReader) keeps them for as long as they are needed.
+    pub seek_back_pins: SeekBackPins,
+    location: RefCell<ParseLocation>,
+    /// Where the innermost failing level, object or property was, until it is reported.
+    failure: RefCell<Option<ParseLocation>>,
+    warnings: RefCell<Vec<ParseWarning>>,
+}
+
+impl ReadContext {
+    pub fn new(save_version: i32) -> Self {
+        ReadContext {
+            save_version,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_string_decoding(mut self, string_decoding: StringDecoding) -> Self {
+        self.string_decoding = string_decoding;
+        self
+    }
+
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: ReadLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_size_validation(mut self, size_validation: SizeValidation) -> Self {
+        self.size_validation = size_validation;
+        self
+    }
+
+    pub fn with_seek_back_pins(mut self, seek_back_pins: SeekBackPins) -> Self {
+        self.seek_back_pins = seek_back_pins;
+        self
+    }
+
+    /// Runs `read` with `offset` pinned, so the reader can seek back to it afterwards.
+    pub(crate) fn pinned<T>(&self, offset: u64, read: impl FnOnce() -> T) -> T {
+        struct Release<'a>(&'a SeekBackPins, u64);
+
+        impl Drop for Release<'_> {
+            fn drop(&mut self) {
+                self.0.release(self.1);
+            }
+        }
+
+        self.seek_back_pins.pin(offset);
+        let _release = Release(&self.seek_back_pins, offset);
+        read()
+    }
+
+    /// Runs `read` with `offset` pinned when resynchronising, so that
+    /// [`check_size`](Self::check_size) can seek back to the declared end of something that
+    /// started or ends there.
+    pub(crate) fn pinned_for_resync<T>(&self, offset: u64, read: impl FnOnce() -> T) -> T {
+        if self.size_validation == SizeValidation::Resync {
+            self.pinned(offset, read)
+        } else {
+            read()
+        }
+    }
+
+    /// Whether vectors, rotators and quaternions are stored with double precision.
+    pub fn is_double_precision(&self) -> bool {
+        self.save_version >= save_version::UNREAL_ENGINE_5
+    }
+
+    /// Where the decoder currently is.
+    pub fn location(&self) -> ParseLocation {
+        self.location.borrow().clone()
+    }
+
+    /// Where the most recent failure happened, or the current location if nothing failed.
+    /// The failure is forgotten, so the next one can be recorded.
+    pub fn take_failure_location(&self) -> ParseLocation {
+        self.failure
+            .borrow_mut()
+            .take()
+            .unwrap_or_else(|| self.location())
+    }
+
+    pub(crate) fn warn(&self, warning: ParseWarning) {
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// Takes the warnings collected in lenient mode so far.
+    pub fn take_warnings(&self) -> Vec<ParseWarning> {
+        std::mem::take(&mut self.warnings.borrow_mut())
+    }
+
+    /// Compares the bytes read since `start` with their declared size.
+    /// With size validation, a mismatch is recorded as a warning at the current location, and when
+    /// resynchronising the reader is moved to the declared end.
+    ///
+    /// Returns the bytes skipped over when the declared end is ahead of the reader, so they can be
+    /// kept with whatever they belong to.
+    pub(crate) fn check_size<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        what: &'static str,
+        start: u64,
+        declared: i64,
+    ) -> BinResult<Vec<u8>> {
+        if self.size_validation == SizeValidation::Off {
+            return Ok(Vec::new());
+        }
+
+        let actual = reader.stream_position()? as i64 - start as i64;
+        if actual != declared {
+            self.warn(ParseWarning {
+                location: self.location(),
+                error: Error::SizeMismatch {
+                    what,
+                    expected: declared,
+                    actual,
+                },
+            });
+            if self.size_validation == SizeValidation::Resync && declared >= 0 {
+                if actual < declared {
+                    let pos = reader.stream_position()?;
+                    let count = limits::check_array_length(pos, "skipped size", declared - actual)?;
+                    return Vec::<u8>::read_options(
+                        reader,
+                        Endian::Little,
+                        VecArgs { count, inner: () },
+                    );
+                }
+                reader.seek(SeekFrom::Start(start + declared as u64))?;
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Compares a count declared at `pos` with the number of elements it describes.
+    /// A mismatch fails the read, or in lenient mode is recorded as a warning at the current
+    /// location and the described elements are read.
+    pub(crate) fn check_count(
+        &self,
+        pos: u64,
+        what: &'static str,
+        expected: usize,
+        declared: i64,
+    ) -> BinResult<()> {
+        if i64::try_from(expected).is_ok_and(|expected| expected == declared) {
+            return Ok(());
+        }
+
+        let error = Error::CountMismatch {
+            what,
+            expected: expected as i64,
+            actual: declared,
+        };
+        if self.lenient {
+            self.warn(ParseWarning {
+                location: self.location(),
+                error,
+            });
+            Ok(())
+        } else {
+            Err(binrw::Error::Custom {
+                pos,
+                err: Box::new(error),
+            })
+        }
+    }
+
+    /// Runs `read` as part of the named level.
+    pub(crate) fn in_level<T>(
+        &self,
+        name: &str,
+        offset: u64,
+        read: impl FnOnce() -> BinResult<T>,
+    ) -> BinResult<T> {
+        let (previous_offset, previous_level, previous_object) = {
+            let mut location = self.location.borrow_mut();
+            (
+                std::mem::replace(&mut location.offset, offset),
+                location.level.replace(name.to_owned()),
+                location.object.take(),
+            )
+        };
+        let result = self.record_failure(read());
+        let mut location = self.location.borrow_mut();
+        location.offset = previous_offset;
+        location.level = previous_level;
+        location.object = previous_object;
+        result
+    }
+
+    /// Runs `read` as part of the named object.
+    pub(crate) fn in_object<T>(
+        &self,
+        instance_name: &str,
+        offset: u64,
+        read: impl FnOnce() -> BinResult<T>,
+    ) -> BinResult<T> {
+        let (previous_offset, previous_object, previous_path) = {
+            let mut location = self.location.borrow_mut();
+            (
+                std::mem::replace(&mut location.offset, offset),
+                location.object.replace(instance_name.to_owned()),
+                std::mem::take(&mut location.property_path),
+            )
+        };
+        let result = self.record_failure(read());
+        let mut location = self.location.borrow_mut();
+        location.offset = previous_offset;
+        location.object = previous_object;
+        location.property_path = previous_path;
+        result
+    }
+
+    /// Runs `read` for a property or element nested in the current one.
+    /// Fails if this would nest properties deeper than [`ReadLimits::max_nesting_depth`].
+    pub(crate) fn in_property<T>(
+        &self,
+        segment: PathSegment,
+        offset: u64,
+        read: impl FnOnce() -> BinResult<T>,
+    ) -> BinResult<T> {
+        let depth = self.location.borrow().property_path.len() + 1;
+        limits::check_nesting_depth(offset, "property nesting depth", depth)?;
+
+        let previous_offset = {
+            let mut location = self.location.borrow_mut();
+            location.property_path.push(segment);
+            std::mem::replace(&mut location.offset, offset)
+        };
+        let result = self.record_failure(read());
+        let mut location = self.location.borrow_mut();
+        location.offset = previous_offset;
+        location.property_path.pop();
+        result
+    }
+
+    /// Remembers the current location if `result` is the first failure since the last report.
+    fn record_failure<T>(&self, result: BinResult<T>) -> BinResult<T> {
+        if result.is_err() {
+            let mut failure = self.failure.borrow_mut();
+            if failure.is_none() {
+                *failure = Some(self.location());
+            }
+        }
+        result
+    }
+}
+
+/// How the sizes that properties and objects declare are checked against the bytes read for them.
+/// Mismatches usually mean the decoder misunderstands a type, for example after a game update.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SizeValidation {
+    /// Sizes are only used where the format depends on them.
+    #[default]
+    Off,
+    /// Mismatches are collected as warnings, and reading continues where the decoder stopped.
+    Report,
+    /// Mismatches are collected as warnings, and reading continues at the declared end.
+    Resync,
+}
+
+/// A problem that lenient mode recovered from, or a size mismatch found by [`SizeValidation`].
+#[derive(Debug)]
+pub struct ParseWarning {
+    pub location: ParseLocation,
+    pub error: Error,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.error)?;
+        let mut source = std::error::Error::source(&self.error);
+        while let Some(error) = source {
+            write!(f, ": {error}")?;
+            source = error.source();
+        }
+        Ok(())
+    }
+}
+
+/// The part of the save body being decoded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseLocation {
+    /// The offset in the decompressed body where the innermost level, object or property started.
+    pub offset: u64,
+    pub level: Option<std::string::String>,
+    /// The instance name of the object.
+    pub object: Option<std::string::String>,
+    pub property_path: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Property(std::string::String),
+    Index(usize),
+}
+
+impl fmt::Display for ParseLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut separator = "";
+        if let Some(level) = &self.level {
+            write!(f, "level {level}")?;
+            separator = ", ";
+        }
+        if let Some(object) = &self.object {
+            write!(f, "{separator}object {object}")?;
+            separator = ", ";
+        }
+        if !self.property_path.is_empty() {
+            write!(f, "{separator}property ")?;
+            for (i, segment) in self.property_path.iter().enumerate() {
+                match segment {
+                    PathSegment::Property(name) if i == 0 => write!(f, "{name}")?,
+                    PathSegment::Property(name) => write!(f, ".{name}")?,
+                    PathSegment::Index(index) => write!(f, "[{index}]")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Reads `read` bytes of a 16 byte buffer, then checks them against `declared`.
+    /// Returns the skipped bytes, where the reader ended up and the size mismatch warnings.
+    fn check(
+        size_validation: SizeValidation,
+        read: u64,
+        declared: i64,
+    ) -> (Vec<u8>, u64, Vec<(i64, i64)>) {
+        let mut reader = Cursor::new((0..16).collect::<Vec<u8>>());
+        reader.set_position(read);
+        let ctx = ReadContext::default().with_size_validation(size_validation);
+
+        let skipped = ctx.check_size(&mut reader, "test", 0, declared).unwrap();
+
+        let warnings = ctx
+            .take_warnings()
+            .into_iter()
+            .map(|warning| match warning.error {
+                Error::SizeMismatch {
+                    what: "test",
+                    expected,
+                    actual,
+                } => (expected, actual),
+                error => panic!("unexpected warning: {error}"),
+            })
+            .collect();
+        (skipped, reader.position(), warnings)
+    }
+
+    #[test]
+    fn off_ignores_mismatch() {
+        assert_eq!(check(SizeValidation::Off, 4, 8), (vec![], 4, vec![]));
+        assert_eq!(check(SizeValidation::Off, 12, 8), (vec![], 12, vec![]));
+    }
+
+    #[test]
+    fn matching_size() {
+        assert_eq!(check(SizeValidation::Report, 8, 8), (vec![], 8, vec![]));
+        assert_eq!(check(SizeValidation::Resync, 8, 8), (vec![], 8, vec![]));
+    }
+
+    #[test]
+    fn report_under_read() {
+        assert_eq!(
+            check(SizeValidation::Report, 4, 8),
+            (vec![], 4, vec![(8, 4)])
+        );
+    }
+
+    #[test]
+    fn report_over_read() {
+        assert_eq!(
+            check(SizeValidation::Report, 12, 8),
+            (vec![], 12, vec![(8, 12)])
+        );
+    }
+
+    #[test]
+    fn resync_under_read_keeps_skipped_bytes() {
+        assert_eq!(
+            check(SizeValidation::Resync, 4, 8),
+            (vec![4, 5, 6, 7], 8, vec![(8, 4)])
+        );
+    }
+
+    #[test]
+    fn resync_over_read_seeks_back() {
+        assert_eq!(
+            check(SizeValidation::Resync, 12, 8),
+            (vec![], 8, vec![(8, 12)])
+        );
+    }
+
+    #[test]
+    fn resync_ignores_negative_size() {
+        assert_eq!(
+            check(SizeValidation::Resync, 4, -1),
+            (vec![], 4, vec![(-1, 4)])
+        );
+    }
+}
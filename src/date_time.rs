@@ -0,0 +1,192 @@
+//! Conversions between Unreal `FDateTime` ticks and Rust time types.
+//!
+//! A tick is 100 nanoseconds, counted from 0001-01-01 00:00:00 UTC.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const TICKS_PER_SECOND: i64 = 10_000_000;
+const NANOS_PER_TICK: u128 = 100;
+/// The number of ticks between 0001-01-01 and the Unix epoch.
+pub const UNIX_EPOCH_TICKS: i64 = 621_355_968_000_000_000;
+
+/// Converts ticks to a [`SystemTime`], or `None` if the platform can't represent the time.
+pub fn ticks_to_system_time(ticks: i64) -> Option<SystemTime> {
+    let since_epoch = i128::from(ticks) - i128::from(UNIX_EPOCH_TICKS);
+    let offset = duration_from_ticks(since_epoch.unsigned_abs());
+    if since_epoch >= 0 {
+        UNIX_EPOCH.checked_add(offset)
+    } else {
+        UNIX_EPOCH.checked_sub(offset)
+    }
+}
+
+/// Converts a [`SystemTime`] to ticks, saturating at the range of an `i64`.
+pub fn system_time_to_ticks(time: SystemTime) -> i64 {
+    let since_epoch = match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => ticks_from_duration(after),
+        Err(before) => -ticks_from_duration(before.duration()),
+    };
+    (i128::from(UNIX_EPOCH_TICKS) + since_epoch).clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
+fn duration_from_ticks(ticks: u128) -> Duration {
+    let seconds = ticks / TICKS_PER_SECOND as u128;
+    let nanos = (ticks % TICKS_PER_SECOND as u128) * NANOS_PER_TICK;
+    Duration::new(seconds as u64, nanos as u32)
+}
+
+fn ticks_from_duration(duration: Duration) -> i128 {
+    (duration.as_nanos() / NANOS_PER_TICK) as i128
+}
+
+#[cfg(feature = "chrono")]
+pub fn ticks_to_chrono(ticks: i64) -> Option<chrono::DateTime<chrono::Utc>> {
+    let since_epoch = ticks.checked_sub(UNIX_EPOCH_TICKS)?;
+    chrono::DateTime::from_timestamp(
+        since_epoch.div_euclid(TICKS_PER_SECOND),
+        (since_epoch.rem_euclid(TICKS_PER_SECOND) * NANOS_PER_TICK as i64) as u32,
+    )
+}
+
+#[cfg(feature = "chrono")]
+pub fn chrono_to_ticks(time: chrono::DateTime<chrono::Utc>) -> i64 {
+    let seconds = i128::from(time.timestamp()) * i128::from(TICKS_PER_SECOND);
+    let ticks = seconds + i128::from(time.timestamp_subsec_nanos()) / NANOS_PER_TICK as i128;
+    (i128::from(UNIX_EPOCH_TICKS) + ticks).clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
+#[cfg(feature = "time")]
+pub fn ticks_to_offset_date_time(ticks: i64) -> Option<time::OffsetDateTime> {
+    let since_epoch = i128::from(ticks) - i128::from(UNIX_EPOCH_TICKS);
+    time::OffsetDateTime::from_unix_timestamp_nanos(since_epoch * NANOS_PER_TICK as i128).ok()
+}
+
+#[cfg(feature = "time")]
+pub fn offset_date_time_to_ticks(time: time::OffsetDateTime) -> i64 {
+    let ticks = time.unix_timestamp_nanos() / NANOS_PER_TICK as i128;
+    (i128::from(UNIX_EPOCH_TICKS) + ticks).clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
+/// Splits ticks into year, month, day, hour, minute and second.
+pub(crate) fn civil_from_ticks(ticks: i64) -> (i64, u32, u32, u32, u32, u32) {
+    const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+    let seconds = ticks.div_euclid(TICKS_PER_SECOND);
+    let days = seconds.div_euclid(SECONDS_PER_DAY);
+    let second_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+
+    // shift the epoch from 0001-01-01 to 0000-03-01 so leap days fall at the end of each year.
+    let days = days + 365 - 59;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (
+        year,
+        month,
+        day,
+        (second_of_day / 3600) as u32,
+        (second_of_day / 60 % 60) as u32,
+        (second_of_day % 60) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The ticks of a time given in seconds since the Unix epoch.
+    fn ticks(unix_seconds: i64) -> i64 {
+        UNIX_EPOCH_TICKS + unix_seconds * TICKS_PER_SECOND
+    }
+
+    #[test]
+    fn civil_epochs() {
+        assert_eq!(civil_from_ticks(0), (1, 1, 1, 0, 0, 0));
+        assert_eq!(civil_from_ticks(UNIX_EPOCH_TICKS), (1970, 1, 1, 0, 0, 0));
+    }
+
+    #[test]
+    fn civil_known_dates() {
+        assert_eq!(
+            civil_from_ticks(ticks(1_709_210_096)),
+            (2024, 2, 29, 12, 34, 56)
+        );
+        assert_eq!(
+            civil_from_ticks(ticks(1_677_628_800)),
+            (2023, 3, 1, 0, 0, 0)
+        );
+        assert_eq!(
+            civil_from_ticks(ticks(1_677_628_799)),
+            (2023, 2, 28, 23, 59, 59)
+        );
+        // 2000 is a leap year even though it is divisible by 100, 1900 is not.
+        assert_eq!(civil_from_ticks(ticks(951_782_400)), (2000, 2, 29, 0, 0, 0));
+        assert_eq!(
+            civil_from_ticks(ticks(-2_203_891_200)),
+            (1900, 3, 1, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn civil_out_of_range_ticks() {
+        // before 0001-01-01 the proleptic calendar continues with year 0.
+        assert_eq!(civil_from_ticks(-1), (0, 12, 31, 23, 59, 59));
+        assert_eq!(civil_from_ticks(i64::MAX), (29228, 9, 14, 2, 48, 5));
+        assert_eq!(civil_from_ticks(i64::MIN), (-29227, 4, 19, 21, 11, 54));
+    }
+
+    #[test]
+    fn system_time_conversions() {
+        assert_eq!(ticks_to_system_time(UNIX_EPOCH_TICKS), Some(UNIX_EPOCH));
+        let time = UNIX_EPOCH + Duration::new(1_709_210_096, 123_456_700);
+        let time_ticks = ticks(1_709_210_096) + 1_234_567;
+        assert_eq!(ticks_to_system_time(time_ticks), Some(time));
+        assert_eq!(system_time_to_ticks(time), time_ticks);
+
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(62_135_596_800);
+        assert_eq!(ticks_to_system_time(0), Some(before_epoch));
+        assert_eq!(system_time_to_ticks(before_epoch), 0);
+    }
+
+    #[test]
+    fn system_time_saturates() {
+        let far_future = UNIX_EPOCH + Duration::from_secs(10_000_000_000_000);
+        assert_eq!(system_time_to_ticks(far_future), i64::MAX);
+        let far_past = UNIX_EPOCH - Duration::from_secs(10_000_000_000_000);
+        assert_eq!(system_time_to_ticks(far_past), i64::MIN);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_conversions() {
+        let time = chrono::DateTime::from_timestamp(1_709_210_096, 123_456_700).unwrap();
+        let time_ticks = ticks(1_709_210_096) + 1_234_567;
+        assert_eq!(ticks_to_chrono(time_ticks), Some(time));
+        assert_eq!(chrono_to_ticks(time), time_ticks);
+        assert_eq!(
+            ticks_to_chrono(-1),
+            chrono::DateTime::from_timestamp(-62_135_596_801, 999_999_900)
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_conversions() {
+        let time =
+            time::OffsetDateTime::from_unix_timestamp_nanos(1_709_210_096_123_456_700).unwrap();
+        let time_ticks = ticks(1_709_210_096) + 1_234_567;
+        assert_eq!(ticks_to_offset_date_time(time_ticks), Some(time));
+        assert_eq!(offset_date_time_to_ticks(time), time_ticks);
+        assert_eq!(ticks_to_offset_date_time(i64::MAX), None);
+    }
+}
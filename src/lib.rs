@@ -1,45 +1,229 @@
 //! https://satisfactory.fandom.com/wiki/Save_files
 //! https://github.com/moritz-h/satisfactory-3d-map/blob/master/docs/SATISFACTORY_SAVE.md#type-and-object-reference
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, Write};
 
-use binrw::{BinRead, BinReaderExt, BinResult};
+use binrw::{BinRead, BinReaderExt, BinResult, BinWrite};
+pub use catalog::{CatalogEntry, CatalogError, CatalogSession, SaveCatalog};
+pub use chunked_body_reader::{ChunkedBodyReader, SeekBackPins};
+pub use context::{ParseLocation, ParseWarning, PathSegment, ReadContext, SizeValidation};
+use flate2::read::ZlibDecoder;
+pub use limits::ReadLimits;
+use limits::{with_read_limits, LimitExceeded};
+pub use map_options::{MapOption, MapOptions};
+pub use mod_metadata::{ModMetadata, ModReference};
+pub use save_data::{LevelData, SaveData, SaveObject};
+pub use save_header::{
+    ESessionVisibility, HashVerification, SaveFileHeader, SaveIdentity, SaveIntegrity,
+    SaveModeFlags, SaveTiming,
+};
 use thiserror::Error;
-use types::{array::Array, string::String};
+use types::{
+    array::{write_length, write_size_prefixed, Array, ArrayArgs},
+    guid::Guid,
+    map::Map,
+    object_data::read_trailing,
+    object_reference_disc::ObjectReferenceDisc,
+    per_streaming_level_save_data::PerStreamingLevelSaveData,
+    persistent_and_runtime_save_data::PersistentAndRuntimeSaveData,
+    property_tag::{PropertyTag, PropertyTagData},
+    quatd::Quatd,
+    rotator::Rotator,
+    soft_object_path::SoftObjectPath,
+    string::{with_string_decoding, Name, String, StringDecoding, StringError},
+    text::Text,
+    unresolved_world_save_data::UnresolvedWorldSaveData,
+    vector2d::Vector2d,
+    vector3d::Vector3d,
+    world_partition_validation_data::WorldPartitionValidationData,
+};
 
-mod types;
+mod catalog;
+pub mod chunked_body_reader;
+mod context;
+pub mod date_time;
+pub mod limits;
+mod map_options;
+mod mod_metadata;
+mod save_data;
+mod save_header;
+pub mod save_header_version;
+pub mod save_version;
+pub mod types;
 
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("failed to read save file")]
-    BinRead(#[from] binrw::Error),
+    BinRead(#[source] binrw::Error),
+    #[error("failed to access save file")]
+    Io(#[source] std::io::Error),
+    #[error("failed to decompress save body chunk {chunk}")]
+    Decompress {
+        chunk: usize,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("bad magic at offset {offset}: found {found}")]
+    BadMagic {
+        offset: u64,
+        found: std::string::String,
+    },
+    #[error("invalid string at offset {offset}")]
+    InvalidString {
+        offset: u64,
+        #[source]
+        reason: StringError,
+    },
+    #[error("read limit exceeded at offset {offset}")]
+    LimitExceeded {
+        offset: u64,
+        #[source]
+        reason: LimitExceeded,
+    },
+    #[error("unknown property type {prop_type}")]
+    UnknownPropertyType { prop_type: std::string::String },
+    #[error("invalid mod metadata")]
+    ModMetadata(#[source] serde_json::Error),
+    #[error("{what} size mismatch: expected {expected} bytes but found {actual}")]
+    SizeMismatch {
+        what: &'static str,
+        expected: i64,
+        actual: i64,
+    },
+    #[error("{what} count mismatch: expected {expected} but found {actual}")]
+    CountMismatch {
+        what: &'static str,
+        expected: i64,
+        actual: i64,
+    },
+    /// Decoding the body failed; `source` is the underlying error.
+    #[error(
+        "failed to decode save body at {position}{}",
+        describe_location(location)
+    )]
+    Body {
+        position: BodyPosition,
+        location: Box<ParseLocation>,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl From<binrw::Error> for Error {
+    fn from(error: binrw::Error) -> Self {
+        match error {
+            binrw::Error::BadMagic { pos, found } => Error::BadMagic {
+                offset: pos,
+                found: format!("{found:?}"),
+            },
+            binrw::Error::Custom { err, pos } => {
+                let err = match err.downcast::<UnknownPropertyType>() {
+                    Ok(unknown) => {
+                        return Error::UnknownPropertyType {
+                            prop_type: unknown.0,
+                        }
+                    }
+                    Err(err) => err,
+                };
+                let err = match err.downcast::<Error>() {
+                    Ok(error) => return *error,
+                    Err(err) => err,
+                };
+                let err = match err.downcast::<LimitExceeded>() {
+                    Ok(reason) => {
+                        return Error::LimitExceeded {
+                            offset: pos,
+                            reason: *reason,
+                        }
+                    }
+                    Err(err) => err,
+                };
+                match err.downcast::<StringError>() {
+                    Ok(reason) => Error::InvalidString {
+                        offset: pos,
+                        reason: *reason,
+                    },
+                    Err(err) => Error::BinRead(binrw::Error::Custom { err, pos }),
+                }
+            }
+            binrw::Error::Io(error) => from_io_error(error),
+            binrw::Error::Backtrace(backtrace) => Error::from(*backtrace.error),
+            binrw::Error::EnumErrors {
+                pos,
+                mut variant_errors,
+            } => match selected_variant_error(pos, &variant_errors) {
+                Some(index) => Error::from(variant_errors.swap_remove(index).1),
+                None => Error::BinRead(binrw::Error::EnumErrors {
+                    pos,
+                    variant_errors,
+                }),
+            },
+            error => Error::BinRead(error),
+        }
+    }
+}
+
+/// Where in the save body an error happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyPosition {
+    /// The offset in the decompressed body.
+    pub offset: u64,
+    /// The compressed chunk containing the offset, if known.
+    pub chunk: Option<ChunkPosition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkPosition {
+    pub index: usize,
+    /// The offset in the chunk's decompressed data.
+    pub offset: u64,
+}
+
+impl std::fmt::Display for BodyPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "offset {}", self.offset)?;
+        if let Some(chunk) = self.chunk {
+            write!(f, " (chunk {}, offset {})", chunk.index, chunk.offset)?;
+        }
+        Ok(())
+    }
+}
+
+fn describe_location(location: &ParseLocation) -> std::string::String {
+    if *location == ParseLocation::default() {
+        std::string::String::new()
+    } else {
+        format!(" in {location}")
+    }
 }
 
 fn adabool(value: u32) -> bool {
     value != 0
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
-pub struct SaveFileHeader {
-    pub save_header_version: i32,
-    pub save_version: i32,
-    pub build_version: i32,
-    pub map_name: String,
-    pub map_options: String,
-    pub session_name: String,
-    pub play_duration_seconds: i32,
-    pub save_date_time: i64,
-    pub session_visibility: i8,
-    pub editor_object_version: i32,
-    pub mod_metadata: String,
-    #[br(map(adabool))]
-    pub is_modded_save: bool,
-    pub save_identifier: String,
-    #[br(map(adabool))]
-    pub is_partitioned_world: bool,
-    pub md5_hash: [u8; 20],
-    #[br(map(adabool))]
-    pub is_creative_mode_enabled: bool,
+/// Converts a bool back into the `u32` that [`adabool`] reads.
+fn to_adabool(value: &bool) -> u32 {
+    u32::from(*value)
+}
+
+/// Reads a floating point number that is stored as a double if `double_precision` is set, and as a
+/// float otherwise.
+#[binrw::parser(reader, endian)]
+fn parse_real(double_precision: bool) -> BinResult<f64> {
+    if double_precision {
+        f64::read_options(reader, endian, ())
+    } else {
+        f32::read_options(reader, endian, ()).map(f64::from)
+    }
+}
+
+/// Writes a floating point number the way [`parse_real`] reads it.
+#[binrw::writer(writer, endian)]
+fn write_real(value: &f64, double_precision: bool) -> BinResult<()> {
+    if double_precision {
+        value.write_options(writer, endian, ())
+    } else {
+        (*value as f32).write_options(writer, endian, ())
+    }
 }
 
 #[derive(Debug, BinRead)]
@@ -53,65 +237,250 @@ pub struct CompressedSaveFileBody {
     pub compressor_num: u8,
     pub compressed_size_summary: i64,
     pub uncompressed_size_summary: i64,
+    #[br(try_map = limits::check_chunk_size("compressed chunk size"))]
     pub compressed_size: i64,
+    #[br(try_map = limits::check_chunk_size("decompressed chunk size"))]
     pub uncompressed_size: i64,
     #[br(count = compressed_size)]
     pub chunk_bytes: Vec<u8>,
 }
 
+/// The decompressed body of an Update 8 save file.
 #[derive(Debug, BinRead)]
-#[br(little)]
+#[br(little, import(ctx: &ReadContext))]
 pub struct SaveFileBody {
+    pub uncompressed_size: i64,
+    pub validation_data: WorldPartitionValidationData,
+    #[br(parse_with = parse_per_level_data, args(ctx))]
+    pub per_level_data: Map<String, PerStreamingLevelSaveData>,
+    #[br(parse_with = parse_persistent_and_runtime_data, args(ctx))]
+    pub persistent_and_runtime_data: PersistentAndRuntimeSaveData,
+    pub unresolved_world_save_data: UnresolvedWorldSaveData,
+}
+
+/// Writes the body with its size computed from the contents, so that it can be written back after
+/// something in it has changed.
+impl BinWrite for SaveFileBody {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_size_prefixed::<i64, _>(writer, endian, |writer| {
+            self.validation_data.write_options(writer, endian, ())?;
+            self.per_level_data.write_options(writer, endian, (true,))?;
+            self.persistent_and_runtime_data
+                .write_options(writer, endian, (true,))?;
+            self.unresolved_world_save_data
+                .write_options(writer, endian, ())
+        })
+    }
+}
+
+/// Reads the per-level map, keeping track of the level being read for error reporting.
+#[binrw::parser(reader, endian)]
+fn parse_per_level_data(ctx: &ReadContext) -> BinResult<Map<String, PerStreamingLevelSaveData>> {
+    let pos = reader.stream_position()?;
+    let count = i32::read_options(reader, endian, ())?;
+    let count = limits::check_array_length(pos, "level count", count.into())?;
+    let mut levels = Vec::new();
+    for _ in 0..count {
+        let name = String::read_options(reader, endian, ())?;
+        let offset = reader.stream_position()?;
+        let level = ctx.in_level(&name.to_string(), offset, || {
+            PerStreamingLevelSaveData::read_options(reader, endian, (ctx,))
+        })?;
+        levels.push((name, level));
+    }
+    Ok(Map(levels))
+}
+
+#[binrw::parser(reader, endian)]
+fn parse_persistent_and_runtime_data(ctx: &ReadContext) -> BinResult<PersistentAndRuntimeSaveData> {
+    let offset = reader.stream_position()?;
+    ctx.in_level(PERSISTENT_LEVEL, offset, || {
+        PersistentAndRuntimeSaveData::read_options(reader, endian, (ctx,))
+    })
+}
+
+/// The decompressed body of an Update 6 or Update 7 save file, with sub-levels followed by the
+/// persistent level.
+#[derive(Debug, BinRead)]
+#[br(little, import(ctx: &ReadContext))]
+pub struct Update6SaveFileBody {
     pub uncompressed_size: i64,
     // pub sublevel_count: i32,
-    #[br(args { inner: LevelBinReadArgs { is_sublevel: true } })]
+    #[br(args { inner: LevelBinReadArgs { is_sublevel: true, ctx } })]
     pub sub_levels: Array<Level>,
-    #[br(args { is_sublevel: false })]
+    #[br(args { is_sublevel: false, ctx })]
     pub persistent_level: Level,
     pub object_references: Array<ObjectReference>,
 }
 
+/// Writes the body with its size computed from the contents, like [`SaveFileBody`].
+impl BinWrite for Update6SaveFileBody {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_size_prefixed::<i64, _>(writer, endian, |writer| {
+            self.sub_levels
+                .write_options(writer, endian, Default::default())?;
+            self.persistent_level.write_options(writer, endian, ())?;
+            self.object_references
+                .write_options(writer, endian, Default::default())
+        })
+    }
+}
+
+/// The decompressed body of a save file from before Update 6, with all objects in a single level.
 #[derive(Debug, BinRead)]
-#[br(little, import { is_sublevel: bool })]
+#[br(little, import(ctx: &ReadContext))]
+pub struct LegacySaveFileBody {
+    pub uncompressed_size: i32,
+    pub object_headers: Array<ObjectHeader>,
+    pub object_count: i32,
+    #[br(parse_with = parse_objects, args(&object_headers.0, object_count, None, ctx))]
+    pub objects: Vec<Object>,
+    pub collected_objects: Array<ObjectReference>,
+}
+
+/// Writes the body with its size and object count computed from the contents, like
+/// [`SaveFileBody`].
+impl BinWrite for LegacySaveFileBody {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_size_prefixed::<i32, _>(writer, endian, |writer| {
+            self.object_headers
+                .write_options(writer, endian, Default::default())?;
+            write_length::<i32, _>(writer, self.objects.len())?.write_options(
+                writer,
+                endian,
+                (),
+            )?;
+            self.objects.write_options(writer, endian, ())?;
+            self.collected_objects
+                .write_options(writer, endian, Default::default())
+        })
+    }
+}
+
+#[derive(Debug, BinRead)]
+#[br(little, import { is_sublevel: bool, ctx: &ReadContext })]
 pub struct Level {
     #[br(if(is_sublevel))]
-    pub sublevel_name: String,
+    pub sublevel_name: Option<String>,
     pub object_header_and_collectables_size: i32,
     pub object_headers: Array<ObjectHeader>,
     pub collectables: Array<ObjectReference>,
     pub objects_size: i32,
     pub object_count: i32,
-    #[br(parse_with = parse_objects, args(&object_headers.0))]
+    #[br(parse_with = parse_objects, args(&object_headers.0, object_count, Some(level_name(&sublevel_name)), ctx))]
     pub objects: Vec<Object>,
     pub collections_2: Array<ObjectReference>,
 }
 
+/// Writes the level with its sizes and object count computed from the contents.
+/// Only sub-levels have a name.
+impl BinWrite for Level {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.sublevel_name.write_options(writer, endian, ())?;
+        write_size_prefixed::<i32, _>(writer, endian, |writer| {
+            self.object_headers
+                .write_options(writer, endian, Default::default())?;
+            self.collectables
+                .write_options(writer, endian, Default::default())
+        })?;
+        write_size_prefixed::<i32, _>(writer, endian, |writer| {
+            write_length::<i32, _>(writer, self.objects.len())?.write_options(
+                writer,
+                endian,
+                (),
+            )?;
+            self.objects.write_options(writer, endian, ())
+        })?;
+        self.collections_2
+            .write_options(writer, endian, Default::default())
+    }
+}
+
+/// The name of the persistent level, which is not stored with its objects.
+const PERSISTENT_LEVEL: &str = "Persistent_Level";
+
+fn level_name(sublevel_name: &Option<String>) -> std::string::String {
+    sublevel_name
+        .as_ref()
+        .map_or_else(|| PERSISTENT_LEVEL.to_owned(), ToString::to_string)
+}
+
 #[binrw::parser(reader, endian)]
-fn parse_objects(headers: &[ObjectHeader]) -> BinResult<Vec<Object>> {
-    let mut objects = Vec::new();
-
-    for header in headers {
-        match header {
-            ObjectHeader::Actor(_) => {
-                let object = ActorObject::read_options(reader, endian, ())?;
-                objects.push(Object::Actor(object));
-            }
-            ObjectHeader::Component(_) => {
-                let object = ComponentObject::read_options(reader, endian, ())?;
-                objects.push(Object::Component(object));
-            }
+fn parse_objects(
+    headers: &[ObjectHeader],
+    object_count: i32,
+    level_name: Option<std::string::String>,
+    ctx: &ReadContext,
+) -> BinResult<Vec<Object>> {
+    let level_offset = reader.stream_position()?;
+    let mut read_objects = || {
+        // the object count is stored right before the objects.
+        let count_pos = level_offset.saturating_sub(4);
+        ctx.check_count(count_pos, "object", headers.len(), object_count.into())?;
+        let mut objects = Vec::new();
+
+        for header in headers {
+            let offset = reader.stream_position()?;
+            let object = ctx.pinned_for_resync(offset, || match header {
+                ObjectHeader::Actor(header) => {
+                    ctx.in_object(&header.instance_name.to_string(), offset, || {
+                        ActorObject::read_options(reader, endian, (&header.type_path, ctx))
+                            .map(Object::Actor)
+                    })
+                }
+                ObjectHeader::Component(header) => {
+                    ctx.in_object(&header.instance_name.to_string(), offset, || {
+                        ComponentObject::read_options(reader, endian, (&header.type_path, ctx))
+                            .map(Object::Component)
+                    })
+                }
+            })?;
+            objects.push(object);
         }
-    }
 
-    Ok(objects)
+        Ok(objects)
+    };
+
+    match level_name {
+        Some(name) => ctx.in_level(&name, level_offset, read_objects),
+        None => read_objects(),
+    }
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub enum ObjectHeader {
-    #[br(magic = 1i32)]
+    #[brw(magic = 1i32)]
     Actor(ActorHeader),
-    #[br(magic = 0i32)]
+    #[brw(magic = 0i32)]
     Component(ComponentHeader),
 }
 
@@ -121,8 +490,8 @@ pub enum ObjectType {
     Component,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct ActorHeader {
     pub type_path: String,
     pub root_object: String,
@@ -141,8 +510,8 @@ pub struct ActorHeader {
     pub was_placed_in_level: i32,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct ComponentHeader {
     pub type_path: String,
     pub root_object: String,
@@ -150,318 +519,989 @@ pub struct ComponentHeader {
     pub parent_actor_name: String,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little, import { object_type: ObjectType })]
+#[derive(Debug, BinRead, BinWrite)]
+#[br(little, import { object_type: ObjectType, class: &String, ctx: &ReadContext })]
+#[bw(little)]
 pub enum Object {
     #[br(pre_assert(matches!(object_type, ObjectType::Actor)))]
-    Actor(ActorObject),
+    Actor(#[br(args(class, ctx))] ActorObject),
     #[br(pre_assert(matches!(object_type, ObjectType::Component)))]
-    Component(ComponentObject),
+    Component(#[br(args(class, ctx))] ComponentObject),
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[binrw::binread]
+#[derive(Debug)]
+#[br(little, import(class: &String, ctx: &ReadContext))]
 pub struct ActorObject {
     pub size: i32,
+    #[br(temp, parse_with = stream_position)]
+    start: u64,
     pub parent_object_root: String,
     pub parent_object_name: String,
     pub components: Array<ObjectReference>,
+    #[br(args(Some(class), ctx))]
     pub properties: PropertyList,
-    pub trailing: [u8; 16],
+    /// Everything after the properties up to the object's size.
+    #[br(parse_with = parse_trailing, args(start, size, ctx))]
+    pub trailing: Vec<u8>,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+/// Writes the object with its size computed from the contents, like
+/// [`ObjectData`](types::object_data::ObjectData).
+impl BinWrite for ActorObject {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_size_prefixed::<i32, _>(writer, endian, |writer| {
+            self.parent_object_root.write_options(writer, endian, ())?;
+            self.parent_object_name.write_options(writer, endian, ())?;
+            self.components
+                .write_options(writer, endian, Default::default())?;
+            self.properties.write_options(writer, endian, (false,))?;
+            self.trailing.write_options(writer, endian, ())
+        })
+    }
+}
+
+#[binrw::binread]
+#[derive(Debug)]
+#[br(little, import(class: &String, ctx: &ReadContext))]
 pub struct ComponentObject {
     pub size: i32,
+    #[br(temp, parse_with = stream_position)]
+    start: u64,
+    #[br(args(Some(class), ctx))]
     pub properties: PropertyList,
-    pub trailing: [u8; 16],
+    /// Everything after the properties up to the object's size.
+    #[br(parse_with = parse_trailing, args(start, size, ctx))]
+    pub trailing: Vec<u8>,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+impl BinWrite for ComponentObject {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_size_prefixed::<i32, _>(writer, endian, |writer| {
+            self.properties.write_options(writer, endian, (false,))?;
+            self.trailing.write_options(writer, endian, ())
+        })
+    }
+}
+
+#[binrw::parser(reader)]
+fn stream_position() -> BinResult<u64> {
+    Ok(reader.stream_position()?)
+}
+
+#[binrw::parser(reader)]
+fn parse_trailing(start: u64, size: i32, ctx: &ReadContext) -> BinResult<Vec<u8>> {
+    read_trailing(reader, "object", start, size, ctx)
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct ObjectReference {
     pub level_name: String,
     pub path_name: String,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little, import { prop_type: String })]
-pub enum Property {
-    #[br(pre_assert(prop_type == "ArrayProperty"))]
-    Array(ArrayProperty),
-    #[br(pre_assert(prop_type == "BoolProperty"))]
-    Bool(BoolProperty),
-    #[br(pre_assert(prop_type == "ByteProperty"))]
-    Byte(ByteProperty),
-    #[br(pre_assert(prop_type == "EnumProperty"))]
+impl From<ObjectReference> for ObjectReferenceDisc {
+    fn from(reference: ObjectReference) -> Self {
+        ObjectReferenceDisc {
+            level_name: reference.level_name,
+            path_name: reference.path_name,
+        }
+    }
+}
+
+/// A single entry in a [`PropertyList`]: the property tag followed by the property's value.
+#[derive(Debug)]
+pub struct Property {
+    pub tag: PropertyTag,
+    pub value: PropertyValue,
+    /// The end of the value that was not decoded, only kept when resynchronising after a size
+    /// mismatch, see [`SizeValidation::Resync`].
+    pub trailing: Vec<u8>,
+}
+
+/// Writes the tag as it is, so its size has to be kept up to date when changing the value.
+impl BinWrite for Property {
+    type Args<'a> = (bool,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (double_precision,): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.tag.write_options(writer, endian, ())?;
+        self.value.write_options(
+            writer,
+            endian,
+            PropertyValueBinWriteArgs {
+                tag_data: Some(&self.tag.data),
+                double_precision,
+            },
+        )?;
+        self.trailing.write_options(writer, endian, ())
+    }
+}
+
+/// The value of a property, either following a [`PropertyTag`] in a [`PropertyList`] or as an
+/// element of an array, set or map property.
+/// Elements have no tag of their own, in which case `tag_data` is `None`.
+/// Values are written with the same tag data they are read with, and with whether vectors are
+/// written with double precision, see [`ReadContext::is_double_precision`].
+#[derive(Debug, BinRead, BinWrite)]
+#[bw(little, import { tag_data: Option<&PropertyTagData>, double_precision: bool })]
+#[br(little, import {
+    prop_type: &Name,
+    tag_data: Option<&PropertyTagData>,
+    owner: Option<PropertyOwner<'_>>,
+    struct_type: Option<&Name>,
+    ctx: &ReadContext,
+})]
+#[br(pre_assert(
+    PROPERTY_TYPES.iter().any(|known| prop_type == known),
+    UnknownPropertyType(prop_type.to_string())
+))]
+pub enum PropertyValue {
+    #[br(pre_assert(*prop_type == "ArrayProperty"))]
+    Array(
+        #[br(args(tag_data, ctx))]
+        #[bw(args(double_precision))]
+        ArrayProperty,
+    ),
+    #[br(pre_assert(*prop_type == "BoolProperty"))]
+    Bool(#[brw(args(tag_data))] BoolProperty),
+    #[br(pre_assert(*prop_type == "ByteProperty"))]
+    Byte(#[br(args(tag_data))] ByteProperty),
+    #[br(pre_assert(*prop_type == "DoubleProperty"))]
+    Double(DoubleProperty),
+    #[br(pre_assert(*prop_type == "EnumProperty"))]
     Enum(EnumProperty),
-    #[br(pre_assert(prop_type == "FloatProperty"))]
+    #[br(pre_assert(*prop_type == "FloatProperty"))]
     Float(FloatProperty),
-    #[br(pre_assert(prop_type == "IntProperty"))]
+    #[br(pre_assert(*prop_type == "IntProperty"))]
     Int(IntProperty),
-    #[br(pre_assert(prop_type == "Int64Property"))]
+    #[br(pre_assert(*prop_type == "Int8Property"))]
+    Int8(Int8Property),
+    #[br(pre_assert(*prop_type == "Int64Property"))]
     Int64(Int64Property),
-    #[br(pre_assert(prop_type == "MapProperty"))]
-    Map(MapProperty),
-    #[br(pre_assert(prop_type == "NameProperty"))]
+    #[br(pre_assert(*prop_type == "MapProperty"))]
+    Map(
+        #[br(args(tag_data, owner, ctx))]
+        #[bw(args(double_precision))]
+        MapProperty,
+    ),
+    #[br(pre_assert(*prop_type == "NameProperty"))]
     Name(NameProperty),
-    #[br(pre_assert(prop_type == "ObjectProperty"))]
+    #[br(pre_assert(*prop_type == "ObjectProperty"))]
     Object(ObjectProperty),
-    #[br(pre_assert(prop_type == "SetProperty"))]
-    Set(SetProperty),
-    #[br(pre_assert(prop_type == "StrProperty"))]
+    #[br(pre_assert(*prop_type == "SetProperty"))]
+    Set(
+        #[br(args(tag_data, owner, ctx))]
+        #[bw(args(double_precision))]
+        SetProperty,
+    ),
+    #[br(pre_assert(*prop_type == "SoftObjectProperty"))]
+    SoftObject(#[br(args(ctx))] SoftObjectProperty),
+    #[br(pre_assert(*prop_type == "StrProperty"))]
     Str(StrProperty),
-    #[br(pre_assert(prop_type == "StructProperty"))]
-    Struct(StructProperty),
-    #[br(pre_assert(prop_type == "TextProperty"))]
+    #[br(pre_assert(*prop_type == "StructProperty"))]
+    Struct(
+        #[br(args(tag_data, struct_type, ctx))]
+        #[bw(args(double_precision))]
+        StructProperty,
+    ),
+    #[br(pre_assert(*prop_type == "TextProperty"))]
     Text(TextProperty),
+    #[br(pre_assert(*prop_type == "UInt32Property"))]
+    UInt32(UInt32Property),
+    #[br(pre_assert(*prop_type == "UInt64Property"))]
+    UInt64(UInt64Property),
+    /// Never read directly, only produced for properties that fail to decode in lenient mode.
+    #[br(pre_assert(false))]
+    Raw(#[br(ignore)] RawProperty),
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+/// A property that could not be decoded, kept as the bytes its tag's size covers.
+#[derive(Debug, Default)]
+pub struct RawProperty {
+    pub name: Name,
+    pub prop_type: Name,
+    pub bytes: Vec<u8>,
+}
+
+impl BinWrite for RawProperty {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.bytes.write_options(writer, endian, ())
+    }
+}
+
+/// Every property type that [`PropertyValue`] can read.
+const PROPERTY_TYPES: &[&str] = &[
+    "ArrayProperty",
+    "BoolProperty",
+    "ByteProperty",
+    "DoubleProperty",
+    "EnumProperty",
+    "FloatProperty",
+    "IntProperty",
+    "Int8Property",
+    "Int64Property",
+    "MapProperty",
+    "NameProperty",
+    "ObjectProperty",
+    "SetProperty",
+    "SoftObjectProperty",
+    "StrProperty",
+    "StructProperty",
+    "TextProperty",
+    "UInt32Property",
+    "UInt64Property",
+];
+
+/// The binrw error raised for a property type not in [`PROPERTY_TYPES`].
+#[derive(Debug)]
+struct UnknownPropertyType(std::string::String);
+
+impl std::fmt::Display for UnknownPropertyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "unknown property type {}", self.0)
+    }
+}
+
+/// Returns an error if a container property is being read without the tag data it depends on.
+fn expect_tag_data<'a, R: Seek>(
+    reader: &mut R,
+    tag_data: Option<&'a PropertyTagData>,
+) -> BinResult<&'a PropertyTagData> {
+    tag_data.ok_or_else(|| binrw::Error::AssertFail {
+        pos: reader.stream_position().unwrap_or_default(),
+        message: "nested container properties are not supported".into(),
+    })
+}
+
+#[derive(Debug)]
 pub struct ArrayProperty {
-    pub size: i32,
-    pub index: i32,
-    pub element_type: String,
-    #[br(pad_before = 1)]
-    #[br(args { inner: PropertyBinReadArgs { prop_type: element_type.clone() } })]
-    pub elements: Array<Property>,
+    /// The tag describing the elements, only present for arrays of structs.
+    pub inner_tag: Option<PropertyTag>,
+    pub elements: Vec<PropertyValue>,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+impl BinRead for ArrayProperty {
+    type Args<'a> = (Option<&'a PropertyTagData>, &'a ReadContext);
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: binrw::Endian,
+        (tag_data, ctx): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let PropertyTagData::Array { inner_type } = expect_tag_data(reader, tag_data)? else {
+            unreachable!("array property tag always has array tag data")
+        };
+
+        let pos = reader.stream_position()?;
+        let count = i32::read_options(reader, endian, ())?;
+        let count = limits::check_array_length(pos, "array length", count.into())?;
+
+        // arrays of structs have a second property tag describing the struct type.
+        let inner_tag = if *inner_type == "StructProperty" {
+            let name = Name::read_options(reader, endian, ())?;
+            Some(PropertyTag::read_options(reader, endian, (name,))?)
+        } else {
+            None
+        };
+
+        let args = PropertyValueBinReadArgs {
+            prop_type: inner_type,
+            tag_data: inner_tag.as_ref().map(|tag| &tag.data),
+            owner: None,
+            struct_type: None,
+            ctx,
+        };
+        let elements = if inner_tag.is_some() {
+            // struct elements are nested property lists, so it is worth tracking which one fails.
+            (0..count)
+                .map(|index| {
+                    let offset = reader.stream_position()?;
+                    ctx.in_property(PathSegment::Index(index), offset, || {
+                        PropertyValue::read_options(reader, endian, args.clone())
+                    })
+                })
+                .collect::<BinResult<_>>()?
+        } else {
+            binrw::helpers::count_with(count, PropertyValue::read_options)(reader, endian, args)?
+        };
+
+        Ok(ArrayProperty {
+            inner_tag,
+            elements,
+        })
+    }
+}
+
+impl BinWrite for ArrayProperty {
+    type Args<'a> = (bool,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (double_precision,): Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_length::<i32, _>(writer, self.elements.len())?.write_options(writer, endian, ())?;
+        self.inner_tag.write_options(writer, endian, ())?;
+        self.elements.write_options(
+            writer,
+            endian,
+            PropertyValueBinWriteArgs {
+                tag_data: self.inner_tag.as_ref().map(|tag| &tag.data),
+                double_precision,
+            },
+        )
+    }
+}
+
+/// Bool properties store their value in the property tag; bool elements store it as an int8.
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little, import(tag_data: Option<&PropertyTagData>))]
 pub struct BoolProperty {
-    #[br(pad_before = 4)]
-    pub index: i32,
-    #[br(map(adabool), pad_after = 1)]
+    #[br(parse_with = parse_bool_value, args(tag_data))]
+    #[bw(write_with = write_bool_value, args(tag_data))]
     pub value: bool,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[binrw::parser(reader, endian)]
+fn parse_bool_value(tag_data: Option<&PropertyTagData>) -> BinResult<bool> {
+    match tag_data {
+        Some(PropertyTagData::Bool { value }) => Ok(*value),
+        _ => Ok(u8::read_options(reader, endian, ())? != 0),
+    }
+}
+
+/// Writes a bool element, or nothing if the value is part of the property tag.
+#[binrw::writer(writer, endian)]
+fn write_bool_value(value: &bool, tag_data: Option<&PropertyTagData>) -> BinResult<()> {
+    match tag_data {
+        Some(PropertyTagData::Bool { .. }) => Ok(()),
+        _ => u8::from(*value).write_options(writer, endian, ()),
+    }
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[br(little, import(tag_data: Option<&PropertyTagData>))]
+#[bw(little)]
 pub struct ByteProperty {
-    pub size: i32,
-    pub index: i32,
-    pub prop_type: String,
-    #[br(args { prop_type: prop_type.clone() }, pad_before = 1)]
+    #[br(args { enum_name: match tag_data {
+        Some(PropertyTagData::Byte { enum_name }) => Some(enum_name),
+        _ => None,
+    } })]
     pub value: BytePropertyValue,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little, import { prop_type: String })]
+/// Byte properties without an enum type, and all byte elements, are stored as a single byte.
+#[derive(Debug, BinRead, BinWrite)]
+#[br(little, import { enum_name: Option<&Name> })]
+#[bw(little)]
 pub enum BytePropertyValue {
-    #[br(pre_assert(prop_type == "None"))]
+    #[br(pre_assert(enum_name.is_none_or(|name| *name == "None")))]
     Byte(i8),
-    #[br(pre_assert(prop_type != "None"))]
+    #[br(pre_assert(enum_name.is_some_and(|name| *name != "None")))]
     String(String),
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
+pub struct DoubleProperty {
+    pub value: f64,
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct EnumProperty {
-    pub size: i32,
-    pub index: i32,
-    pub prop_type: String,
-    #[br(pad_before = 1)]
-    pub value: String,
+    pub value: Name,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct FloatProperty {
-    pub size: i32,
-    pub index: i32,
-    #[br(pad_before = 1)]
     pub value: f32,
 }
 
-#[derive(Debug, BinRead, Default)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite, Default)]
+#[brw(little)]
 pub struct IntProperty {
-    pub size: i32,
-    pub index: i32,
-    #[br(pad_before = 1)]
     pub value: i32,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
+pub struct Int8Property {
+    pub value: i8,
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct Int64Property {
-    pub size: i32,
-    pub index: i32,
-    #[br(pad_before = 1)]
     pub value: i64,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug)]
 pub struct MapProperty {
-    pub size: i32,
-    pub index: i32,
-    pub key_type: String,
-    pub value_type: String,
-    #[br(pad_before = 1)]
-    pub mode_type: i32,
-    #[br(args { inner: KVPairBinReadArgs { key_type: key_type.clone(), value_type: value_type.clone() } })]
-    pub elements: Array<KVPair>,
+    pub num_keys_to_remove: i32,
+    pub elements: Vec<KVPair>,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little, import { key_type: String, value_type: String })]
+impl BinRead for MapProperty {
+    type Args<'a> = (
+        Option<&'a PropertyTagData>,
+        Option<PropertyOwner<'a>>,
+        &'a ReadContext,
+    );
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: binrw::Endian,
+        (tag_data, owner, ctx): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let PropertyTagData::Map {
+            key_type,
+            value_type,
+        } = expect_tag_data(reader, tag_data)?
+        else {
+            unreachable!("map property tag always has map tag data")
+        };
+
+        let key_struct_type = element_struct_type(MAP_KEY_STRUCT_TYPES, owner);
+        let value_struct_type = element_struct_type(MAP_VALUE_STRUCT_TYPES, owner);
+
+        let num_keys_to_remove = i32::read_options(reader, endian, ())?;
+        let elements = Array::<KVPair>::read_options(
+            reader,
+            endian,
+            ArrayArgs {
+                inner: KVPairBinReadArgs {
+                    key_type,
+                    value_type,
+                    key_struct_type: key_struct_type.as_ref(),
+                    value_struct_type: value_struct_type.as_ref(),
+                    ctx,
+                },
+            },
+        )?
+        .0;
+
+        Ok(MapProperty {
+            num_keys_to_remove,
+            elements,
+        })
+    }
+}
+
+impl BinWrite for MapProperty {
+    type Args<'a> = (bool,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (double_precision,): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.num_keys_to_remove.write_options(writer, endian, ())?;
+        write_length::<i32, _>(writer, self.elements.len())?.write_options(writer, endian, ())?;
+        self.elements
+            .write_options(writer, endian, (double_precision,))
+    }
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[bw(little, import(double_precision: bool))]
+#[br(little, import {
+    key_type: &Name,
+    value_type: &Name,
+    key_struct_type: Option<&Name>,
+    value_struct_type: Option<&Name>,
+    ctx: &ReadContext,
+})]
 pub struct KVPair {
-    #[br(args { prop_type: key_type.clone() })]
-    pub key: Property,
-    #[br(args { prop_type: value_type.clone() })]
-    pub value: Property,
+    #[br(args { prop_type: key_type, tag_data: None, owner: None, struct_type: key_struct_type, ctx })]
+    #[bw(args { tag_data: None, double_precision })]
+    pub key: PropertyValue,
+    #[br(args { prop_type: value_type, tag_data: None, owner: None, struct_type: value_struct_type, ctx })]
+    #[bw(args { tag_data: None, double_precision })]
+    pub value: PropertyValue,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct NameProperty {
-    pub size: i32,
-    pub index: i32,
-    #[br(pad_before = 1)]
-    pub value: String,
+    pub value: Name,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct ObjectProperty {
-    pub size: i32,
-    pub index: i32,
-    #[br(pad_before = 1)]
     pub level_name: String,
     pub path_name: String,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+/// The class of the object, or the type of the struct, that a property belongs to and the
+/// property's name.
+#[derive(Debug, Clone, Copy)]
+pub struct PropertyOwner<'a> {
+    pub class: &'a String,
+    pub property: &'a Name,
+}
+
+/// Struct types of map keys, which are not stored in the save, by the class or struct type and
+/// name of the map property. See the MapProperty section of `docs/save.md`.
+const MAP_KEY_STRUCT_TYPES: &[(&str, &str, &str)] = &[
+    (
+        "/Script/FactoryGame.FGFoliageRemovalSubsystem",
+        "mSaveData",
+        "IntVector",
+    ),
+    (
+        "/Script/FactoryGame.FGFoliageRemovalSubsystem",
+        "mUnresolvedSaveData",
+        "IntVector",
+    ),
+];
+
+/// Struct types of map values, like [`MAP_KEY_STRUCT_TYPES`].
+const MAP_VALUE_STRUCT_TYPES: &[(&str, &str, &str)] = &[
+    (
+        "/Game/FactoryGame/Events/BP_EventSubsystem.BP_EventSubsystem_C",
+        "mStoredCalendarData",
+        "CalendarData",
+    ),
+    (
+        "/Game/FactoryGame/Events/BP_EventSubsystem.BP_EventSubsystem_C",
+        "mCalendarData",
+        "CalendarData",
+    ),
+    (
+        "/Script/FactoryGame.FGFoliageRemovalSubsystem",
+        "mSaveData",
+        "FoliageRemovalSaveDataPerCell",
+    ),
+    (
+        "/Script/FactoryGame.FGFoliageRemovalSubsystem",
+        "mUnresolvedSaveData",
+        "FoliageRemovalUnresolvedSaveDataPerCell",
+    ),
+    (
+        "/Script/FactoryGame.FGStatisticsSubsystem",
+        "mActorsBuiltCount",
+        "ActorBuiltData",
+    ),
+    (
+        "FoliageRemovalSaveDataPerCell",
+        "SaveDataMap",
+        "FoliageRemovalSaveDataForFoliageType",
+    ),
+    (
+        "FoliageRemovalUnresolvedSaveDataPerCell",
+        "SaveDataMap",
+        "FoliageRemovalSaveDataForFoliageType",
+    ),
+    ("LBBalancerData", "mIndexMapping", "LBBalancerIndexing"),
+];
+
+/// Struct types of set elements, like [`MAP_KEY_STRUCT_TYPES`].
+/// See the SetProperty section of `docs/save.md`.
+const SET_STRUCT_TYPES: &[(&str, &str, &str)] = &[(
+    "/Script/FactoryGame.FGFoliageRemoval",
+    "mRemovalLocations",
+    "Vector",
+)];
+
+fn element_struct_type(
+    struct_types: &[(&str, &str, &str)],
+    owner: Option<PropertyOwner>,
+) -> Option<Name> {
+    let owner = owner?;
+    struct_types
+        .iter()
+        .find(|(class, property, _)| *owner.class == *class && *owner.property == *property)
+        .map(|(_, _, struct_type)| Name::from(*struct_type))
+}
+
+#[derive(Debug)]
 pub struct SetProperty {
-    pub size: i32,
-    pub index: i32,
-    pub element_type: String,
-    #[br(pad_before = 1 + 4)]
-    pub element_count: i32,
-    #[br(args { inner: PropertyBinReadArgs { prop_type: element_type.clone() } })]
-    pub elements: Array<Property>,
+    pub num_elements_to_remove: i32,
+    pub elements: Vec<PropertyValue>,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+impl BinRead for SetProperty {
+    type Args<'a> = (
+        Option<&'a PropertyTagData>,
+        Option<PropertyOwner<'a>>,
+        &'a ReadContext,
+    );
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: binrw::Endian,
+        (tag_data, owner, ctx): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let PropertyTagData::Set { inner_type } = expect_tag_data(reader, tag_data)? else {
+            unreachable!("set property tag always has set tag data")
+        };
+
+        let struct_type = element_struct_type(SET_STRUCT_TYPES, owner);
+
+        let num_elements_to_remove = i32::read_options(reader, endian, ())?;
+        let elements = Array::<PropertyValue>::read_options(
+            reader,
+            endian,
+            ArrayArgs {
+                inner: PropertyValueBinReadArgs {
+                    prop_type: inner_type,
+                    tag_data: None,
+                    owner: None,
+                    struct_type: struct_type.as_ref(),
+                    ctx,
+                },
+            },
+        )?
+        .0;
+
+        Ok(SetProperty {
+            num_elements_to_remove,
+            elements,
+        })
+    }
+}
+
+impl BinWrite for SetProperty {
+    type Args<'a> = (bool,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        (double_precision,): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.num_elements_to_remove
+            .write_options(writer, endian, ())?;
+        write_length::<i32, _>(writer, self.elements.len())?.write_options(writer, endian, ())?;
+        self.elements.write_options(
+            writer,
+            endian,
+            PropertyValueBinWriteArgs {
+                tag_data: None,
+                double_precision,
+            },
+        )
+    }
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
+#[br(import(ctx: &ReadContext))]
+pub struct SoftObjectProperty {
+    #[br(args(ctx.save_version))]
+    pub value: SoftObjectPath,
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct StrProperty {
-    pub size: i32,
-    pub index: i32,
-    #[br(pad_before = 1)]
     pub value: String,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+/// Struct properties take their struct type from the property tag.
+/// Struct elements of maps and sets have no tag, so their type is looked up from the property they
+/// belong to. The type of elements that are not listed in `docs/save.md` is unknown.
+#[derive(Debug, BinRead, BinWrite)]
+#[br(little, import(tag_data: Option<&PropertyTagData>, struct_type: Option<&Name>, ctx: &ReadContext))]
+#[bw(little, import(double_precision: bool))]
 pub struct StructProperty {
-    pub size: i32,
-    pub index: i32,
-    pub struct_type: String,
-    #[br(pad_before = 8 + 8 + 1, args { is_struct_property_payload: true })]
+    #[br(calc = match tag_data {
+        Some(PropertyTagData::Struct { struct_name, .. }) => Some(struct_name.clone()),
+        _ => struct_type.cloned(),
+    })]
+    #[bw(ignore)]
+    pub struct_type: Option<Name>,
+    #[br(args { struct_type: struct_type.as_ref(), ctx })]
+    #[bw(args(double_precision))]
     pub typed_data: TypedData,
 }
 
-#[derive(Debug, BinRead)]
-#[br(little, import { is_struct_property_payload: bool })]
+/// Struct types that are serialised in their own binary format rather than as a property list.
+const BINARY_STRUCT_TYPES: &[&str] = &[
+    "Box",
+    "Color",
+    "FluidBox",
+    "Guid",
+    "IntPoint",
+    "IntVector",
+    "InventoryItem",
+    "LBBalancerIndexing",
+    "LinearColor",
+    "Quat",
+    "RailroadTrackPosition",
+    "Rotator",
+    "SoftClassPath",
+    "Vector2D",
+    "Vector",
+];
+
+fn is_struct_type(struct_type: Option<&Name>, name: &str) -> bool {
+    struct_type.is_some_and(|struct_type| *struct_type == name)
+}
+
+fn is_binary_struct_type(struct_type: Option<&Name>) -> bool {
+    BINARY_STRUCT_TYPES
+        .iter()
+        .any(|name| is_struct_type(struct_type, name))
+}
+
+/// The payload of a struct property, selected by its struct type.
+/// Unknown struct types, and structs of unknown type, are read as property lists.
+#[derive(Debug, BinRead, BinWrite)]
+#[br(little, import { struct_type: Option<&Name>, ctx: &ReadContext })]
+#[bw(little, import(double_precision: bool))]
 pub enum TypedData {
-    PropertyList(PropertyList),
+    #[br(pre_assert(is_struct_type(struct_type, "Box")))]
     Box {
-        min_x: f32,
-        min_y: f32,
-        min_z: f32,
-        max_x: f32,
-        max_y: f32,
-        max_z: f32,
-        #[br(map(adabool))]
-        is_value: bool,
+        #[br(args(ctx.is_double_precision()))]
+        #[bw(args(double_precision))]
+        min: Vector3d,
+        #[br(args(ctx.is_double_precision()))]
+        #[bw(args(double_precision))]
+        max: Vector3d,
+        #[br(map = |value: u8| value != 0)]
+        #[bw(map = |value: &bool| u8::from(*value))]
+        is_valid: bool,
     },
+    #[br(pre_assert(is_struct_type(struct_type, "Color")))]
+    Color { b: u8, g: u8, r: u8, a: u8 },
+    #[br(pre_assert(is_struct_type(struct_type, "FluidBox")))]
     FluidBox(f32),
+    #[br(pre_assert(is_struct_type(struct_type, "Guid")))]
+    Guid(Guid),
+    #[br(pre_assert(is_struct_type(struct_type, "IntPoint")))]
+    IntPoint { x: i32, y: i32 },
+    #[br(pre_assert(is_struct_type(struct_type, "IntVector")))]
+    IntVector { x: i32, y: i32, z: i32 },
+    #[br(pre_assert(is_struct_type(struct_type, "InventoryItem")))]
     InventoryItem {
-        #[br(pad_before = 4)]
-        item_type: String,
-        level_name: String,
-        path_name: String,
-        #[br(if(is_struct_property_payload))]
-        extra: IntProperty,
-    },
-    LinearColor {
-        r: f32,
-        g: f32,
-        b: f32,
-        a: f32,
+        item_class: ObjectReferenceDisc,
+        item_state: ObjectReferenceDisc,
     },
-    Quat {
-        x: f32,
-        y: f32,
-        z: f32,
-        w: f32,
+    #[br(pre_assert(is_struct_type(struct_type, "LBBalancerIndexing")))]
+    LBBalancerIndexing {
+        normal_index: i32,
+        overflow_index: i32,
+        filter_index: i32,
     },
+    #[br(pre_assert(is_struct_type(struct_type, "LinearColor")))]
+    LinearColor { r: f32, g: f32, b: f32, a: f32 },
+    #[br(pre_assert(is_struct_type(struct_type, "Quat")))]
+    Quat(
+        #[br(args(ctx.is_double_precision()))]
+        #[bw(args(double_precision))]
+        Quatd,
+    ),
+    #[br(pre_assert(is_struct_type(struct_type, "RailroadTrackPosition")))]
     RailroadTrackPosition {
         level_name: String,
         path_name: String,
         offset: f32,
         forward: f32,
     },
-    Vector {
-        x: f32,
-        y: f32,
-        z: f32,
-    },
+    #[br(pre_assert(is_struct_type(struct_type, "Rotator")))]
+    Rotator(
+        #[br(args(ctx.is_double_precision()))]
+        #[bw(args(double_precision))]
+        Rotator,
+    ),
+    #[br(pre_assert(is_struct_type(struct_type, "SoftClassPath")))]
+    SoftClassPath(#[br(args(ctx.save_version))] SoftObjectPath),
+    #[br(pre_assert(is_struct_type(struct_type, "Vector2D")))]
+    Vector2D(
+        #[br(args(ctx.is_double_precision()))]
+        #[bw(args(double_precision))]
+        Vector2d,
+    ),
+    #[br(pre_assert(is_struct_type(struct_type, "Vector")))]
+    Vector(
+        #[br(args(ctx.is_double_precision()))]
+        #[bw(args(double_precision))]
+        Vector3d,
+    ),
+    #[br(pre_assert(!is_binary_struct_type(struct_type)))]
+    PropertyList(
+        #[br(args(struct_type, ctx))]
+        #[bw(args(double_precision))]
+        PropertyList,
+    ),
 }
 
-#[derive(Debug, BinRead)]
-#[br(little)]
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
 pub struct TextProperty {
-    pub size: i32,
-    pub index: i32,
-    #[br(pad_before = 1)]
-    pub flags: i32,
-    pub history_type: i8,
-    #[br(map(adabool))]
-    pub is_culture_invariant: bool,
-    pub value: String,
+    #[br(args(0))]
+    pub value: Text,
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
+pub struct UInt32Property {
+    pub value: u32,
+}
+
+#[derive(Debug, BinRead, BinWrite)]
+#[brw(little)]
+pub struct UInt64Property {
+    pub value: u64,
 }
 
 #[derive(Debug)]
 pub struct PropertyList(pub Vec<Property>);
 
+/// Properties are read with the class of the object or the type of the struct they belong to, if
+/// it is known.
 impl BinRead for PropertyList {
-    type Args<'a> = ();
+    type Args<'a> = (Option<&'a String>, &'a ReadContext);
 
     fn read_options<R: Read + Seek>(
         reader: &mut R,
         endian: binrw::Endian,
-        (): Self::Args<'_>,
+        (class, ctx): Self::Args<'_>,
     ) -> binrw::BinResult<Self> {
-        // read properties until a special "None" property is encountered.
-        // properties start with a name string, then a type string, then the property data.
+        // read properties until a special "None" property name is encountered.
+        // properties start with a property tag, then the property data.
         let mut properties = Vec::new();
 
         loop {
-            let name = String::read_options(reader, endian, ())?;
+            let offset = reader.stream_position()?;
+            let name = Name::read_options(reader, endian, ())?;
             if name == "None" {
                 break;
             }
 
-            let prop_type = String::read_options(reader, endian, ())?;
-            properties.push(Property::read_options(
-                reader,
-                endian,
-                PropertyBinReadArgs { prop_type },
-            )?);
+            let segment = PathSegment::Property(name.to_string());
+            let property = ctx.in_property(segment, offset, || {
+                let tag = PropertyTag::read_options(reader, endian, (name,))?;
+                let value_start = reader.stream_position()?;
+                let mut read_value = || match PropertyValue::read_options(
+                    reader,
+                    endian,
+                    PropertyValueBinReadArgs {
+                        prop_type: &tag.prop_type,
+                        tag_data: Some(&tag.data),
+                        owner: class.map(|class| PropertyOwner {
+                            class,
+                            property: &tag.name,
+                        }),
+                        struct_type: None,
+                        ctx,
+                    },
+                ) {
+                    Ok(value) => {
+                        let trailing =
+                            ctx.check_size(reader, "property", value_start, tag.size.into())?;
+                        Ok((value, trailing))
+                    }
+                    Err(error) if ctx.lenient => {
+                        read_raw_property(reader, endian, &tag, value_start, error, ctx)
+                            .map(|value| (value, Vec::new()))
+                    }
+                    Err(error) => Err(error),
+                };
+                // recovering and resynchronising seek back to the value, however large it was.
+                let (value, trailing) =
+                    if ctx.lenient || ctx.size_validation == SizeValidation::Resync {
+                        ctx.pinned(value_start, read_value)?
+                    } else {
+                        read_value()?
+                    };
+                Ok(Property {
+                    tag,
+                    value,
+                    trailing,
+                })
+            })?;
+            properties.push(property);
         }
 
         Ok(PropertyList(properties))
     }
 }
 
+/// Writes the properties followed by the terminating "None" property name.
+/// The `bool` argument is whether vectors are written with double precision.
+impl BinWrite for PropertyList {
+    type Args<'a> = (bool,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.0.write_options(writer, endian, args)?;
+        Name::from("None").write_options(writer, endian, ())
+    }
+}
+
+/// Skips over a property value that failed to decode by re-reading the bytes its tag's size
+/// covers, and records the failure as a warning.
+fn read_raw_property<R: Read + Seek>(
+    reader: &mut R,
+    endian: binrw::Endian,
+    tag: &PropertyTag,
+    value_start: u64,
+    error: binrw::Error,
+    ctx: &ReadContext,
+) -> BinResult<PropertyValue> {
+    // without a usable size there is nothing to skip to, so the property can't be recovered.
+    let Ok(count) = limits::check_array_length(value_start, "property size", tag.size.into())
+    else {
+        return Err(error);
+    };
+    reader.seek(std::io::SeekFrom::Start(value_start))?;
+    let bytes = Vec::<u8>::read_options(reader, endian, binrw::VecArgs { count, inner: () })?;
+
+    ctx.warn(ParseWarning {
+        location: ctx.take_failure_location(),
+        error: error.into(),
+    });
+    Ok(PropertyValue::Raw(RawProperty {
+        name: tag.name.clone(),
+        prop_type: tag.prop_type.clone(),
+        bytes,
+    }))
+}
+
 pub struct Parser<R> {
     data: R,
+    string_decoding: StringDecoding,
+    lenient: bool,
+    limits: ReadLimits,
+    size_validation: SizeValidation,
 }
 
 impl<R> Parser<R>
@@ -469,15 +1509,54 @@ where
     R: Read + Seek,
 {
     pub fn new(data: R) -> Self {
-        Parser { data }
+        Parser {
+            data,
+            string_decoding: StringDecoding::default(),
+            lenient: false,
+            limits: ReadLimits::default(),
+            size_validation: SizeValidation::default(),
+        }
+    }
+
+    /// Sets how strictly strings in the header and body are checked.
+    pub fn with_string_decoding(mut self, string_decoding: StringDecoding) -> Self {
+        self.string_decoding = string_decoding;
+        self
+    }
+
+    /// Sets whether properties that fail to decode are kept as [`RawProperty`] instead of failing
+    /// the whole body. The problems are collected in [`SaveData::warnings`].
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Sets the limits on sizes declared by the header and body.
+    pub fn with_limits(mut self, limits: ReadLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets whether the sizes declared in the body are checked, see [`SizeValidation`].
+    /// Mismatches are collected in [`SaveData::warnings`].
+    pub fn with_size_validation(mut self, size_validation: SizeValidation) -> Self {
+        self.size_validation = size_validation;
+        self
+    }
+
+    pub fn limits(&self) -> &ReadLimits {
+        &self.limits
     }
 
     pub fn read_header(&mut self) -> Result<SaveFileHeader, Error> {
-        self.data.read_le().map_err(Error::BinRead)
+        with_read_limits(self.limits, || {
+            with_string_decoding(self.string_decoding, || self.data.read_le())
+        })
+        .map_err(Error::from)
     }
 
     pub fn read_compressed_body_chunk(&mut self) -> Result<Option<CompressedSaveFileBody>, Error> {
-        match self.data.read_le() {
+        match with_read_limits(self.limits, || self.data.read_le()) {
             Ok(chunk) => Ok(Some(chunk)),
             Err(e) => match e {
                 binrw::Error::Io(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => {
@@ -487,4 +1566,532 @@ where
             },
         }
     }
+
+    /// Checks the header's MD5 hash against the rest of the file.
+    /// This must be called right after [`read_header`](Parser::read_header) and consumes the
+    /// remaining data.
+    pub fn verify_hash(&mut self, header: &SaveFileHeader) -> Result<HashVerification, Error> {
+        header.integrity.verify(&mut self.data)
+    }
+
+    /// Returns a reader that inflates the remaining body chunks one at a time as they are read.
+    /// This must be called after [`read_header`](Parser::read_header).
+    pub fn body_reader(&mut self) -> ChunkedBodyReader<'_, R> {
+        ChunkedBodyReader::new(self)
+    }
+
+    /// Reads all remaining body chunks and inflates them into a single buffer.
+    /// Each chunk's size and the total size of all chunks are checked against their headers.
+    ///
+    /// With the `parallel` feature, the chunks are inflated concurrently.
+    pub fn read_decompressed_body(&mut self) -> Result<Vec<u8>, Error> {
+        let mut chunks = Vec::new();
+        let mut declared_size = 0;
+        while let Some(chunk) = self.read_compressed_body_chunk()? {
+            check_decompressed_body_limit(&self.limits, declared_size, &chunk)?;
+            declared_size += chunk.uncompressed_size as u64;
+            chunks.push(chunk);
+        }
+
+        let body = inflate_chunks(&chunks)?;
+        let expected_size = chunks
+            .iter()
+            .map(|chunk| chunk.uncompressed_size_summary)
+            .sum();
+        check_decompressed_body_size(&body, expected_size)?;
+        Ok(body)
+    }
+
+    /// Reads, decompresses and decodes the body following the given header.
+    /// The body is inflated incrementally, so the decompressed body is never held in memory as a
+    /// whole. This must be called after [`read_header`](Parser::read_header).
+    pub fn read_body(&mut self, header: &SaveFileHeader) -> Result<SaveData, Error> {
+        let string_decoding = self.string_decoding;
+        let lenient = self.lenient;
+        let limits = self.limits;
+        let size_validation = self.size_validation;
+        let mut reader = self.body_reader();
+
+        // the body starts with its own size, not including the size field itself.
+        // saves from before Update 6 store it as an int32, later ones as an int64.
+        let declared_size = if header.save_version < save_version::PER_LEVEL_STREAMING {
+            i64::from(reader.read_le::<i32>()?)
+        } else {
+            reader.read_le::<i64>()?
+        };
+        let body_start = reader.stream_position().map_err(from_io_error)?;
+
+        reader.rewind().map_err(from_io_error)?;
+        let ctx = ReadContext::new(header.save_version)
+            .with_string_decoding(string_decoding)
+            .with_lenient(lenient)
+            .with_limits(limits)
+            .with_size_validation(size_validation)
+            .with_seek_back_pins(reader.pins());
+        let body = reader
+            .read_le_args::<SaveData>((&ctx,))
+            .map_err(|error| body_error(error, &ctx, &reader))?;
+
+        // everything after the decoded body still has to be inflated to know the real size.
+        let consumed = reader.stream_position().map_err(from_io_error)?;
+        let remaining = std::io::copy(&mut reader, &mut std::io::sink()).map_err(from_io_error)?;
+        let actual_size = (consumed + remaining - body_start) as i64;
+        if declared_size != actual_size {
+            return Err(Error::SizeMismatch {
+                what: "save body",
+                expected: declared_size,
+                actual: actual_size,
+            });
+        }
+
+        Ok(body)
+    }
+}
+
+/// Inflates the chunks one after another into a single buffer.
+#[cfg(not(feature = "parallel"))]
+fn inflate_chunks(chunks: &[CompressedSaveFileBody]) -> Result<Vec<u8>, Error> {
+    let mut body = Vec::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        inflate_chunk(chunk, index, &mut body)?;
+    }
+    Ok(body)
+}
+
+/// Inflates the chunks concurrently into a single buffer.
+#[cfg(feature = "parallel")]
+fn inflate_chunks(chunks: &[CompressedSaveFileBody]) -> Result<Vec<u8>, Error> {
+    use rayon::prelude::*;
+
+    // every chunk is an independent zlib stream, so they can be inflated in any order as long
+    // as they are put back together in the order they were read.
+    let inflated = chunks
+        .par_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut data = Vec::new();
+            inflate_chunk(chunk, index, &mut data).map(|()| data)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(inflated.concat())
+}
+
+/// Inflates a single body chunk onto the end of `body`, checking its size against the chunk header.
+pub(crate) fn inflate_chunk(
+    chunk: &CompressedSaveFileBody,
+    index: usize,
+    body: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let chunk_start = body.len();
+    // stop just past the declared size, so a chunk that inflates to far more than it claims can't
+    // exhaust memory before the size check below.
+    ZlibDecoder::new(chunk.chunk_bytes.as_slice())
+        .take(chunk.uncompressed_size as u64 + 1)
+        .read_to_end(body)
+        .map_err(|source| Error::Decompress {
+            chunk: index,
+            source,
+        })?;
+
+    let chunk_size = (body.len() - chunk_start) as i64;
+    if chunk_size != chunk.uncompressed_size {
+        return Err(Error::SizeMismatch {
+            what: "decompressed chunk",
+            expected: chunk.uncompressed_size,
+            actual: chunk_size,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns an error if inflating `chunk` after `inflated` bytes would exceed
+/// [`ReadLimits::max_decompressed_size`].
+pub(crate) fn check_decompressed_body_limit(
+    limits: &ReadLimits,
+    inflated: u64,
+    chunk: &CompressedSaveFileBody,
+) -> Result<(), Error> {
+    let size = inflated + chunk.uncompressed_size as u64;
+    LimitExceeded::check(
+        "decompressed body size",
+        size as i64,
+        limits.max_decompressed_size,
+    )
+    .map(|_| ())
+    .map_err(|reason| Error::LimitExceeded {
+        offset: inflated,
+        reason,
+    })
+}
+
+fn check_decompressed_body_size(body: &[u8], expected_size: i64) -> Result<(), Error> {
+    if body.len() as i64 != expected_size {
+        return Err(Error::SizeMismatch {
+            what: "decompressed body",
+            expected: expected_size,
+            actual: body.len() as i64,
+        });
+    }
+
+    Ok(())
+}
+
+/// Recovers the crate error that a [`ChunkedBodyReader`] had to pass through `std::io`.
+fn from_io_error(error: std::io::Error) -> Error {
+    if error.get_ref().is_some_and(|inner| inner.is::<Error>()) {
+        let inner = error.into_inner().expect("checked above");
+        *inner.downcast::<Error>().expect("checked above")
+    } else {
+        Error::BinRead(binrw::Error::Io(error))
+    }
+}
+
+/// Attaches the position and location the decoder was at to an error from decoding the body.
+fn body_error<R>(error: binrw::Error, ctx: &ReadContext, reader: &ChunkedBodyReader<R>) -> Error
+where
+    R: Read + Seek,
+{
+    let location = ctx.take_failure_location();
+    let offset = error_offset(&error).unwrap_or(location.offset);
+    Error::Body {
+        position: BodyPosition {
+            offset,
+            chunk: reader.chunk_position(offset),
+        },
+        location: Box::new(location),
+        source: Box::new(error.into()),
+    }
+}
+
+/// Finds the error of the one variant whose `pre_assert` or magic matched, for enums like
+/// [`PropertyValue`] and [`TextHistoryType`](types::text::TextHistoryType) that select their
+/// variant up front. binrw reports the errors of every variant, but the others failed without
+/// reading anything.
+fn selected_variant_error(pos: u64, variant_errors: &[(&str, binrw::Error)]) -> Option<usize> {
+    let mut selected = variant_errors.iter().enumerate().filter(|(_, (_, error))| {
+        !matches!(error, binrw::Error::AssertFail { pos: assert_pos, message }
+            if *assert_pos == pos && message.starts_with("assertion failed"))
+            && !matches!(error, binrw::Error::BadMagic { pos: magic_pos, .. } if *magic_pos == pos)
+    });
+    match (selected.next(), selected.next()) {
+        (Some((index, _)), None) => Some(index),
+        _ => None,
+    }
+}
+
+/// The offset binrw recorded for an error, if any.
+fn error_offset(error: &binrw::Error) -> Option<u64> {
+    match error {
+        binrw::Error::BadMagic { pos, .. }
+        | binrw::Error::AssertFail { pos, .. }
+        | binrw::Error::Custom { pos, .. }
+        | binrw::Error::NoVariantMatch { pos } => Some(*pos),
+        binrw::Error::Backtrace(backtrace) => error_offset(&backtrace.error),
+        binrw::Error::EnumErrors {
+            pos,
+            variant_errors,
+        } => match selected_variant_error(*pos, variant_errors) {
+            Some(index) => error_offset(&variant_errors[index].1),
+            None => Some(*pos),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as i32 + 1).to_le_bytes().to_vec();
+        data.extend(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    /// A property with the given type-specific tag data and value, without a property GUID.
+    fn property(name: &str, prop_type: &str, tag_data: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut data = string(name);
+        data.extend(string(prop_type));
+        data.extend((value.len() as i32).to_le_bytes());
+        data.extend(0i32.to_le_bytes());
+        data.extend(tag_data);
+        data.push(0);
+        data.extend(value);
+        data
+    }
+
+    /// A property list holding one container property with the given tag data and value.
+    fn property_list(name: &str, prop_type: &str, tag_data: &[&str], value: &[u8]) -> Vec<u8> {
+        let tag_data: Vec<u8> = tag_data.iter().flat_map(|name| string(name)).collect();
+        let mut data = property(name, prop_type, &tag_data, value);
+        data.extend(string("None"));
+        data
+    }
+
+    fn read(class: &str, data: Vec<u8>) -> PropertyValue {
+        let ctx = ReadContext::new(save_version::UNREAL_ENGINE_5);
+        let class = String::from(class);
+        let mut properties =
+            PropertyList::read_le_args(&mut Cursor::new(data), (Some(&class), &ctx))
+                .unwrap()
+                .0;
+        properties.remove(0).value
+    }
+
+    #[test]
+    fn map_struct_types_are_looked_up() {
+        let mut value = 0i32.to_le_bytes().to_vec();
+        value.extend(1i32.to_le_bytes());
+        for coordinate in [1i32, 2, 3] {
+            value.extend(coordinate.to_le_bytes());
+        }
+        value.extend(property_list(
+            "SaveDataMap",
+            "MapProperty",
+            &["IntProperty", "IntProperty"],
+            &[0; 8],
+        ));
+        let data = property_list(
+            "mSaveData",
+            "MapProperty",
+            &["StructProperty", "StructProperty"],
+            &value,
+        );
+
+        let PropertyValue::Map(map) = read("/Script/FactoryGame.FGFoliageRemovalSubsystem", data)
+        else {
+            panic!("expected a map property");
+        };
+        let KVPair { key, value } = &map.elements[0];
+        let PropertyValue::Struct(key) = key else {
+            panic!("expected a struct key");
+        };
+        assert!(matches!(
+            key.typed_data,
+            TypedData::IntVector { x: 1, y: 2, z: 3 }
+        ));
+        let PropertyValue::Struct(value) = value else {
+            panic!("expected a struct value");
+        };
+        assert_eq!(
+            value
+                .struct_type
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("FoliageRemovalSaveDataPerCell")
+        );
+        let TypedData::PropertyList(properties) = &value.typed_data else {
+            panic!("expected a property list struct");
+        };
+        assert_eq!(properties.0[0].tag.name, "SaveDataMap");
+    }
+
+    #[test]
+    fn binary_map_struct_values_are_looked_up() {
+        let mut value = 0i32.to_le_bytes().to_vec();
+        value.extend(1i32.to_le_bytes());
+        value.extend(string("Persistent_Level"));
+        value.extend(string(
+            "Persistent_Level:PersistentLevel.Build_ConveyorAttachmentSplitter",
+        ));
+        for index in [0i32, -1, 2] {
+            value.extend(index.to_le_bytes());
+        }
+        let data = property_list(
+            "mIndexMapping",
+            "MapProperty",
+            &["ObjectProperty", "StructProperty"],
+            &value,
+        );
+
+        let PropertyValue::Map(map) = read("LBBalancerData", data) else {
+            panic!("expected a map property");
+        };
+        let PropertyValue::Struct(value) = &map.elements[0].value else {
+            panic!("expected a struct value");
+        };
+        assert!(matches!(
+            value.typed_data,
+            TypedData::LBBalancerIndexing {
+                normal_index: 0,
+                overflow_index: -1,
+                filter_index: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn set_struct_types_are_looked_up() {
+        let mut value = 0i32.to_le_bytes().to_vec();
+        value.extend(1i32.to_le_bytes());
+        for coordinate in [1f64, 2.0, 3.0] {
+            value.extend(coordinate.to_le_bytes());
+        }
+        let data = property_list(
+            "mRemovalLocations",
+            "SetProperty",
+            &["StructProperty"],
+            &value,
+        );
+
+        let PropertyValue::Set(set) = read("/Script/FactoryGame.FGFoliageRemoval", data) else {
+            panic!("expected a set property");
+        };
+        let PropertyValue::Struct(element) = &set.elements[0] else {
+            panic!("expected a struct element");
+        };
+        let TypedData::Vector(vector) = &element.typed_data else {
+            panic!("expected a vector");
+        };
+        assert_eq!((vector.x, vector.y, vector.z), (1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn unlisted_struct_elements_are_property_lists() {
+        let mut value = 0i32.to_le_bytes().to_vec();
+        value.extend(1i32.to_le_bytes());
+        value.extend(string("None"));
+        let data = property_list("mOther", "SetProperty", &["StructProperty"], &value);
+
+        let PropertyValue::Set(set) = read("/Script/FactoryGame.FGFoliageRemoval", data) else {
+            panic!("expected a set property");
+        };
+        let PropertyValue::Struct(element) = &set.elements[0] else {
+            panic!("expected a struct element");
+        };
+        assert!(element.struct_type.is_none());
+        assert!(
+            matches!(&element.typed_data, TypedData::PropertyList(properties) if properties.0.is_empty())
+        );
+    }
+
+    /// One property of every kind, in a list that ends with an undecodable property.
+    fn all_properties() -> Vec<u8> {
+        let struct_tag = |struct_name: &str| {
+            let mut data = string(struct_name);
+            data.extend([0; 16]);
+            data
+        };
+
+        let mut data = property("mInt", "IntProperty", &[], &7i32.to_le_bytes());
+        data.extend(property("mBool", "BoolProperty", &[1], &[]));
+        data.extend(property("mByte", "ByteProperty", &string("None"), &[3]));
+        data.extend(property(
+            "mEnum",
+            "ByteProperty",
+            &string("EStack"),
+            &string("EStack::Full"),
+        ));
+        data.extend(property(
+            "mFloat",
+            "FloatProperty",
+            &[],
+            &1.5f32.to_le_bytes(),
+        ));
+        data.extend(property("mName", "NameProperty", &[], &string("Name")));
+
+        let vector: Vec<u8> = [1f64, 2.0, 3.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        data.extend(property(
+            "mLocation",
+            "StructProperty",
+            &struct_tag("Vector"),
+            &vector,
+        ));
+
+        let mut inventory = property("NumItems", "IntProperty", &[], &5i32.to_le_bytes());
+        inventory.extend(string("None"));
+        data.extend(property(
+            "mInventory",
+            "StructProperty",
+            &struct_tag("InventoryStack"),
+            &inventory,
+        ));
+
+        let mut colors = 2i32.to_le_bytes().to_vec();
+        colors.extend(property(
+            "mColors",
+            "StructProperty",
+            &struct_tag("LinearColor"),
+            &[0; 16],
+        ));
+        colors.extend([0x3f; 16]);
+        data.extend(property(
+            "mColors",
+            "ArrayProperty",
+            &string("StructProperty"),
+            &colors,
+        ));
+
+        let mut bools = 2i32.to_le_bytes().to_vec();
+        bools.extend([1, 0]);
+        data.extend(property(
+            "mBools",
+            "ArrayProperty",
+            &string("BoolProperty"),
+            &bools,
+        ));
+
+        let mut map = 0i32.to_le_bytes().to_vec();
+        map.extend(1i32.to_le_bytes());
+        map.extend(7i32.to_le_bytes());
+        map.extend(string("seven"));
+        let mut map_tag = string("IntProperty");
+        map_tag.extend(string("StrProperty"));
+        data.extend(property("mMap", "MapProperty", &map_tag, &map));
+
+        let mut set = 0i32.to_le_bytes().to_vec();
+        set.extend(1i32.to_le_bytes());
+        set.extend(9u32.to_le_bytes());
+        data.extend(property(
+            "mSet",
+            "SetProperty",
+            &string("UInt32Property"),
+            &set,
+        ));
+
+        let mut text = 0u32.to_le_bytes().to_vec();
+        text.push(0);
+        text.extend(string("Namespace"));
+        text.extend(string("Key"));
+        text.extend(string("Source"));
+        data.extend(property("mText", "TextProperty", &[], &text));
+
+        // a property GUID follows the tag data.
+        data.extend(string("mGuid"));
+        data.extend(string("Int64Property"));
+        data.extend(8i32.to_le_bytes());
+        data.extend(0i32.to_le_bytes());
+        data.push(1);
+        data.extend([0xab; 16]);
+        data.extend(64i64.to_le_bytes());
+
+        data.extend(property("mUnknown", "FancyProperty", &[], &[1, 2, 3, 4]));
+        data.extend(string("None"));
+        data
+    }
+
+    #[test]
+    fn property_list_round_trip() {
+        let data = all_properties();
+        let ctx = ReadContext::new(save_version::UNREAL_ENGINE_5).with_lenient(true);
+        let properties = PropertyList::read_le_args(&mut Cursor::new(&data), (None, &ctx)).unwrap();
+        assert!(matches!(
+            properties.0.last().map(|property| &property.value),
+            Some(PropertyValue::Raw(_))
+        ));
+
+        let mut written = Cursor::new(Vec::new());
+        properties
+            .write_le_args(&mut written, (ctx.is_double_precision(),))
+            .unwrap();
+        assert_eq!(written.into_inner(), data);
+    }
 }
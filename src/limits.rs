@@ -0,0 +1,119 @@
+use std::cell::Cell;
+
+use binrw::BinResult;
+
+/// Upper bounds on the sizes a save file may declare, so that a corrupted or hostile length field
+/// fails with [`Error::LimitExceeded`](crate::Error::LimitExceeded) instead of allocating
+/// gigabytes.
+///
+/// The defaults are well above what real saves use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadLimits {
+    /// The most elements an array, set or map may declare. Also bounds the length of strings and
+    /// of properties kept as raw bytes.
+    pub max_array_length: u64,
+    /// The most bytes a single body chunk may declare, compressed or decompressed.
+    pub max_chunk_size: u64,
+    /// The most bytes the decompressed body may have across all chunks.
+    pub max_decompressed_size: u64,
+    /// How deeply properties may be nested inside structs, arrays, sets and maps.
+    pub max_nesting_depth: usize,
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        ReadLimits {
+            max_array_length: 16 * 1024 * 1024,
+            max_chunk_size: 1024 * 1024,
+            max_decompressed_size: 2 * 1024 * 1024 * 1024,
+            max_nesting_depth: 64,
+        }
+    }
+}
+
+/// A declared size that is negative or larger than its [`ReadLimits`] allow.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{what} {requested} is out of range, the limit is {max}")]
+pub struct LimitExceeded {
+    pub what: &'static str,
+    pub requested: i64,
+    pub max: u64,
+}
+
+impl LimitExceeded {
+    /// Returns `requested` if it is between zero and `max`.
+    pub(crate) fn check(what: &'static str, requested: i64, max: u64) -> Result<u64, Self> {
+        u64::try_from(requested)
+            .ok()
+            .filter(|&size| size <= max)
+            .ok_or(LimitExceeded {
+                what,
+                requested,
+                max,
+            })
+    }
+}
+
+thread_local! {
+    static LIMITS: Cell<ReadLimits> = Cell::new(ReadLimits::default());
+}
+
+/// Reads everything inside `read` with the given limits.
+///
+/// Like [`with_string_decoding`](crate::types::string::with_string_decoding), the limits are set
+/// for the current thread because arrays and strings are read without access to the read
+/// arguments of their parents.
+pub fn with_read_limits<T>(limits: ReadLimits, read: impl FnOnce() -> T) -> T {
+    struct Restore(ReadLimits);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            LIMITS.set(self.0);
+        }
+    }
+
+    let _restore = Restore(LIMITS.replace(limits));
+    read()
+}
+
+/// The limits set for the current thread.
+pub(crate) fn current() -> ReadLimits {
+    LIMITS.get()
+}
+
+/// Checks an element count read at `pos` against [`ReadLimits::max_array_length`].
+pub(crate) fn check_array_length(pos: u64, what: &'static str, length: i64) -> BinResult<usize> {
+    LimitExceeded::check(what, length, current().max_array_length)
+        .map(|length| length as usize)
+        .map_err(|error| binrw::Error::Custom {
+            pos,
+            err: Box::new(error),
+        })
+}
+
+/// Checks the size of a buffer within the body read at `pos` against
+/// [`ReadLimits::max_decompressed_size`], as no part of the body can be larger than the body.
+pub(crate) fn check_body_size(pos: u64, what: &'static str, size: i64) -> BinResult<u64> {
+    LimitExceeded::check(what, size, current().max_decompressed_size).map_err(|error| {
+        binrw::Error::Custom {
+            pos,
+            err: Box::new(error),
+        }
+    })
+}
+
+/// Checks how deeply something read at `pos` is nested against
+/// [`ReadLimits::max_nesting_depth`].
+pub(crate) fn check_nesting_depth(pos: u64, what: &'static str, depth: usize) -> BinResult<()> {
+    LimitExceeded::check(what, depth as i64, current().max_nesting_depth as u64)
+        .map(|_| ())
+        .map_err(|error| binrw::Error::Custom {
+            pos,
+            err: Box::new(error),
+        })
+}
+
+/// Checks a chunk size against [`ReadLimits::max_chunk_size`], for use with `try_map`.
+pub(crate) fn check_chunk_size(what: &'static str) -> impl Fn(i64) -> Result<i64, LimitExceeded> {
+    move |size| LimitExceeded::check(what, size, current().max_chunk_size).map(|_| size)
+}
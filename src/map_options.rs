@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::ESessionVisibility;
+
+/// The URL-style options of [`SaveIdentity::map_options`](crate::SaveIdentity::map_options),
+/// e.g. `?startloc=Grass Fields?sessionName=Test?Visibility=SV_FriendsOnly`.
+///
+/// Options keep their order and exact spelling, so formatting the parsed options with
+/// [`Display`](fmt::Display) gives back the original string. Keys are compared case-insensitively,
+/// like the game does.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MapOptions {
+    /// Anything before the first `?`, which is usually empty.
+    pub prefix: std::string::String,
+    pub options: Vec<MapOption>,
+}
+
+/// A single `?key=value` option. Flags like `?listen` have no value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapOption {
+    pub key: std::string::String,
+    pub value: Option<std::string::String>,
+}
+
+impl MapOptions {
+    pub const START_LOCATION: &'static str = "startloc";
+    pub const SESSION_NAME: &'static str = "sessionName";
+    pub const VISIBILITY: &'static str = "Visibility";
+    pub const ADVANCED_GAME_SETTINGS: &'static str = "advancedGameSettings";
+
+    pub fn parse(options: &str) -> Self {
+        let Some((prefix, options)) = options.split_once('?') else {
+            return MapOptions {
+                prefix: options.to_owned(),
+                options: Vec::new(),
+            };
+        };
+
+        let options = options
+            .split('?')
+            .map(|option| match option.split_once('=') {
+                Some((key, value)) => MapOption {
+                    key: key.to_owned(),
+                    value: Some(value.to_owned()),
+                },
+                None => MapOption {
+                    key: option.to_owned(),
+                    value: None,
+                },
+            })
+            .collect();
+
+        MapOptions {
+            prefix: prefix.to_owned(),
+            options,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&MapOption> {
+        self.options
+            .iter()
+            .find(|option| option.key.eq_ignore_ascii_case(key))
+    }
+
+    /// The value of the option, or `None` if it is missing or a flag.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.get(key)?.value.as_deref()
+    }
+
+    /// Whether the option is present, with or without a value.
+    pub fn has(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Interprets the option as a boolean: a bare flag or a value of `true` or `1` is `true`.
+    pub fn flag(&self, key: &str) -> bool {
+        self.get(key).is_some_and(|option| match &option.value {
+            None => true,
+            Some(value) => value.eq_ignore_ascii_case("true") || value == "1",
+        })
+    }
+
+    /// Sets the value of an existing option in place, or appends a new one.
+    pub fn set(&mut self, key: &str, value: Option<&str>) {
+        let value = value.map(str::to_owned);
+        match self
+            .options
+            .iter_mut()
+            .find(|option| option.key.eq_ignore_ascii_case(key))
+        {
+            Some(option) => option.value = value,
+            None => self.options.push(MapOption {
+                key: key.to_owned(),
+                value,
+            }),
+        }
+    }
+
+    /// Removes every option with the key, returning whether there was any.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let len = self.options.len();
+        self.options
+            .retain(|option| !option.key.eq_ignore_ascii_case(key));
+        self.options.len() != len
+    }
+
+    pub fn start_location(&self) -> Option<&str> {
+        self.value(Self::START_LOCATION)
+    }
+
+    pub fn session_name(&self) -> Option<&str> {
+        self.value(Self::SESSION_NAME)
+    }
+
+    pub fn visibility(&self) -> Option<&str> {
+        self.value(Self::VISIBILITY)
+    }
+
+    pub fn session_visibility(&self) -> Option<ESessionVisibility> {
+        self.visibility()
+            .and_then(ESessionVisibility::from_option_name)
+    }
+
+    /// Whether advanced game settings were enabled when the session was created.
+    pub fn has_advanced_game_settings(&self) -> bool {
+        self.flag(Self::ADVANCED_GAME_SETTINGS)
+    }
+}
+
+impl fmt::Display for MapOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.prefix)?;
+        for option in &self.options {
+            write!(f, "?{}", option.key)?;
+            if let Some(value) = &option.value {
+                write!(f, "={value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for options in [
+            "",
+            "?",
+            "Persistent_Level",
+            "Persistent_Level?listen",
+            "?startloc=Grass Fields?sessionName=Test?Visibility=SV_FriendsOnly",
+            "?sessionName=a=b?empty=?listen??advancedGameSettings",
+        ] {
+            assert_eq!(MapOptions::parse(options).to_string(), options);
+        }
+    }
+
+    #[test]
+    fn accessors() {
+        let options = MapOptions::parse(
+            "?startloc=Grass Fields?SESSIONNAME=Test?Visibility=SV_FriendsOnly?advancedGameSettings",
+        );
+        assert_eq!(options.prefix, "");
+        assert_eq!(options.start_location(), Some("Grass Fields"));
+        assert_eq!(options.session_name(), Some("Test"));
+        assert_eq!(
+            options.session_visibility(),
+            Some(ESessionVisibility::FriendsOnly)
+        );
+        assert!(options.has_advanced_game_settings());
+        assert!(!options.flag("listen"));
+    }
+
+    #[test]
+    fn edit_keeps_order() {
+        let mut options = MapOptions::parse("?startloc=Grass Fields?sessionName=Test?listen");
+        options.set("SessionName", Some("Renamed"));
+        options.set(MapOptions::VISIBILITY, Some("SV_Private"));
+        assert!(options.remove("listen"));
+        assert!(!options.remove("listen"));
+        assert_eq!(
+            options.to_string(),
+            "?startloc=Grass Fields?sessionName=Renamed?Visibility=SV_Private"
+        );
+    }
+}
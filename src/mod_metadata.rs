@@ -0,0 +1,137 @@
+use serde::Deserialize;
+
+use crate::Error;
+
+/// The reference Satisfactory Mod Loader lists itself under.
+const SML_REFERENCE: &str = "SML";
+
+/// The mod information Satisfactory Mod Loader stores as JSON in
+/// [`SaveModeFlags::mod_metadata`](crate::SaveModeFlags::mod_metadata).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ModMetadata {
+    /// The version of the metadata format.
+    #[serde(default)]
+    pub version: Option<i64>,
+    #[serde(default)]
+    pub full_map_name: Option<std::string::String>,
+    /// Every mod that was loaded when the save was made, including SML itself.
+    #[serde(default)]
+    pub mods: Vec<ModReference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ModReference {
+    /// The mod reference used by the mod repository, e.g. `"SML"` or `"FicsItNetworks"`.
+    pub reference: std::string::String,
+    /// The display name of the mod.
+    #[serde(default)]
+    pub name: std::string::String,
+    pub version: std::string::String,
+}
+
+impl ModMetadata {
+    pub fn parse(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(Error::ModMetadata)
+    }
+
+    pub fn find(&self, reference: &str) -> Option<&ModReference> {
+        self.mods.iter().find(|m| m.reference == reference)
+    }
+
+    /// The version of Satisfactory Mod Loader the save was made with.
+    pub fn sml_version(&self) -> Option<&str> {
+        self.find(SML_REFERENCE).map(|m| m.version.as_str())
+    }
+
+    /// The mods the save depends on, which is every listed mod except SML itself.
+    pub fn dependencies(&self) -> impl Iterator<Item = &ModReference> {
+        self.mods.iter().filter(|m| m.reference != SML_REFERENCE)
+    }
+
+    /// The dependencies whose reference is not in `installed`.
+    pub fn missing_dependencies<'a>(
+        &'a self,
+        installed: &'a [&str],
+    ) -> impl Iterator<Item = &'a ModReference> {
+        self.dependencies()
+            .filter(|m| !installed.contains(&m.reference.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA: &str = r#"{
+        "Version": 1,
+        "FullMapName": "Persistent_Level",
+        "Mods": [
+            { "Reference": "SML", "Name": "Satisfactory Mod Loader", "Version": "3.7.0" },
+            { "Reference": "FicsItNetworks", "Name": "FicsIt-Networks", "Version": "0.3.19" },
+            { "Reference": "PowerChecker", "Version": "1.0.0" }
+        ]
+    }"#;
+
+    fn references<'a>(mods: impl Iterator<Item = &'a ModReference>) -> Vec<&'a str> {
+        mods.map(|m| m.reference.as_str()).collect()
+    }
+
+    #[test]
+    fn valid_metadata() {
+        let metadata = ModMetadata::parse(METADATA).unwrap();
+        assert_eq!(metadata.version, Some(1));
+        assert_eq!(metadata.full_map_name.as_deref(), Some("Persistent_Level"));
+        assert_eq!(metadata.sml_version(), Some("3.7.0"));
+        assert_eq!(
+            metadata.find("FicsItNetworks").map(|m| m.name.as_str()),
+            Some("FicsIt-Networks")
+        );
+        assert_eq!(metadata.find("PowerChecker").unwrap().name, "");
+        assert!(metadata.find("RefinedPower").is_none());
+    }
+
+    #[test]
+    fn dependencies_exclude_sml() {
+        let metadata = ModMetadata::parse(METADATA).unwrap();
+        assert_eq!(
+            references(metadata.dependencies()),
+            ["FicsItNetworks", "PowerChecker"]
+        );
+        assert_eq!(
+            references(metadata.missing_dependencies(&["SML", "PowerChecker"])),
+            ["FicsItNetworks"]
+        );
+        assert_eq!(references(metadata.missing_dependencies(&[])).len(), 2);
+    }
+
+    #[test]
+    fn missing_fields() {
+        let metadata = ModMetadata::parse("{}").unwrap();
+        assert_eq!(metadata, ModMetadata::default());
+        assert_eq!(metadata.sml_version(), None);
+        assert_eq!(metadata.dependencies().count(), 0);
+
+        // a mod must at least have a reference and a version.
+        for json in [
+            r#"{ "Mods": [{ "Version": "1.0.0" }] }"#,
+            r#"{ "Mods": [{ "Reference": "SML" }] }"#,
+        ] {
+            assert!(matches!(
+                ModMetadata::parse(json),
+                Err(Error::ModMetadata(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn malformed_json() {
+        for json in ["", "{", r#"{ "Mods": {} }"#, r#"{ "Version": "1" }"#] {
+            assert!(matches!(
+                ModMetadata::parse(json),
+                Err(Error::ModMetadata(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,953 @@
+use std::io::{Read, Seek, Write};
+
+use binrw::{BinRead, BinResult, BinWrite, Endian};
+
+use crate::{
+    limits::with_read_limits,
+    save_version,
+    types::{
+        actor_save_header::ActorSaveHeader,
+        array::{write_length, write_size_prefixed},
+        data_blob::{write_data_blob, DataBlob},
+        generic_object_save_header::GenericObjectSaveHeader,
+        map::Map,
+        object_base_save_header::ObjectBaseSaveHeader,
+        object_data::{ActorReferences, ObjectData},
+        object_reference_disc::ObjectReferenceDisc,
+        object_save_header::ObjectSaveHeader,
+        string::{with_string_decoding, String},
+        toc_blob::{write_toc_blob, TocBlob},
+        transform::Transform,
+        world_partition_validation_data::WorldPartitionValidationData,
+    },
+    Error, LegacySaveFileBody, Level, Object, ObjectHeader, ParseWarning, ReadContext,
+    SaveFileBody, Update6SaveFileBody,
+};
+
+/// A decoded save body, independent of the format it was stored in.
+///
+/// The format is selected from the save version: saves from before Update 6 are read as a
+/// [`LegacySaveFileBody`], Update 6 and Update 7 saves as an [`Update6SaveFileBody`] and later
+/// saves as a [`SaveFileBody`].
+#[derive(Debug)]
+pub struct SaveData {
+    /// The sub-levels in save order, followed by the persistent level.
+    pub levels: Vec<LevelData>,
+    /// Only present since Update 8.
+    pub validation_data: Option<WorldPartitionValidationData>,
+    /// Actors destroyed in levels that are not otherwise part of the save, keyed by level name.
+    /// Only present since Update 8.
+    pub level_to_destroyed_actors: Map<String, Vec<ObjectReferenceDisc>>,
+    /// Destroyed actors that could not be attributed to any level.
+    pub unresolved_destroyed_actors: Vec<ObjectReferenceDisc>,
+    /// The problems skipped over in lenient mode and the size mismatches found by size validation.
+    pub warnings: Vec<ParseWarning>,
+}
+
+#[derive(Debug)]
+pub struct LevelData {
+    /// The name of a sub-level, or `None` for the persistent level.
+    pub name: Option<String>,
+    pub objects: Vec<SaveObject>,
+    /// The destroyed actors stored with a sub-level since Update 8, or before the level's objects
+    /// in Update 6 and Update 7 saves.
+    pub destroyed_actors: Vec<ObjectReferenceDisc>,
+    /// The destroyed actors stored in the level's TOC blob, which only has them if there are any.
+    /// Only present since Update 8.
+    pub toc_destroyed_actors: Option<Vec<ObjectReferenceDisc>>,
+    /// Data after the level's objects that was not decoded, see [`DataBlob::trailing`].
+    pub trailing: Vec<u8>,
+    /// The declared size of the level's objects, which is computed again when writing.
+    /// Only present in Update 6 and Update 7 saves.
+    pub objects_size: Option<i32>,
+    /// The destroyed actors listed again after the level's objects.
+    /// Only present in Update 6 and Update 7 saves.
+    pub destroyed_actors_after_objects: Vec<ObjectReferenceDisc>,
+}
+
+/// An object together with the header that describes it.
+#[derive(Debug)]
+pub struct SaveObject {
+    pub header: GenericObjectSaveHeader,
+    /// The save version the object was written with.
+    /// Saves from before Update 8 only record this for the whole save.
+    pub save_version: i32,
+    pub should_migrate_object_refs_to_persistent: bool,
+    pub data: ObjectData,
+}
+
+impl SaveData {
+    pub fn persistent_level(&self) -> Option<&LevelData> {
+        self.levels.iter().find(|level| level.name.is_none())
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = &SaveObject> {
+        self.levels.iter().flat_map(|level| &level.objects)
+    }
+}
+
+impl LevelData {
+    /// The destroyed actors from every list of the level they are stored in.
+    pub fn all_destroyed_actors(&self) -> impl Iterator<Item = &ObjectReferenceDisc> {
+        self.destroyed_actors
+            .iter()
+            .chain(self.toc_destroyed_actors.iter().flatten())
+            .chain(&self.destroyed_actors_after_objects)
+    }
+}
+
+impl BinRead for SaveData {
+    type Args<'a> = (&'a ReadContext,);
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        (ctx,): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        with_read_limits(ctx.limits, || {
+            with_string_decoding(ctx.string_decoding, || {
+                let save = if ctx.save_version < save_version::PER_LEVEL_STREAMING {
+                    let body = LegacySaveFileBody::read_options(reader, endian, (ctx,))?;
+                    SaveData::from_legacy(body, ctx.save_version)
+                } else if ctx.save_version < save_version::UNREAL_ENGINE_5 {
+                    let body = Update6SaveFileBody::read_options(reader, endian, (ctx,))?;
+                    SaveData::from_update6(body, ctx.save_version)
+                } else {
+                    let body = SaveFileBody::read_options(reader, endian, (ctx,))?;
+                    SaveData::try_from(body)
+                };
+                save.map_err(|error| binrw::Error::Custom {
+                    pos: reader.stream_position().unwrap_or_default(),
+                    err: Box::new(error),
+                })
+            })
+        })
+        .map(|save| SaveData {
+            warnings: ctx.take_warnings(),
+            ..save
+        })
+    }
+}
+
+/// Writes the body in the format of the given save version, the way [`SaveData`] reads it.
+/// Sizes and counts are computed from the contents, and the persistent level has to be the only
+/// level of saves from before Update 6.
+impl BinWrite for SaveData {
+    type Args<'a> = (i32,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (save_version,): Self::Args<'_>,
+    ) -> BinResult<()> {
+        if save_version < save_version::PER_LEVEL_STREAMING {
+            self.write_legacy(writer, endian)
+        } else if save_version < save_version::UNREAL_ENGINE_5 {
+            self.write_update6(writer, endian)
+        } else {
+            self.write_update8(writer, endian)
+        }
+    }
+}
+
+impl SaveData {
+    fn write_legacy<W: Write + Seek>(&self, writer: &mut W, endian: Endian) -> BinResult<()> {
+        let level = match &self.levels[..] {
+            [level] if level.name.is_none() => level,
+            _ => {
+                return Err(binrw::Error::AssertFail {
+                    pos: writer.stream_position()?,
+                    message: "saves from before Update 6 only have the persistent level".to_owned(),
+                })
+            }
+        };
+
+        write_size_prefixed::<i32, _>(writer, endian, |writer| {
+            write_headers(writer, endian, &level.objects)?;
+            write_legacy_objects(writer, endian, &level.objects)?;
+            write_references(writer, endian, &self.unresolved_destroyed_actors)
+        })
+    }
+
+    fn write_update6<W: Write + Seek>(&self, writer: &mut W, endian: Endian) -> BinResult<()> {
+        let persistent_level = self.persistent_level_for_writing(writer)?;
+        write_size_prefixed::<i64, _>(writer, endian, |writer| {
+            let sub_levels: Vec<_> = self.sub_levels().collect();
+            write_length::<i32, _>(writer, sub_levels.len())?.write_options(writer, endian, ())?;
+            for level in sub_levels.into_iter().chain([persistent_level]) {
+                level.name.write_options(writer, endian, ())?;
+                write_size_prefixed::<i32, _>(writer, endian, |writer| {
+                    write_headers(writer, endian, &level.objects)?;
+                    write_references(writer, endian, &level.destroyed_actors)
+                })?;
+                write_size_prefixed::<i32, _>(writer, endian, |writer| {
+                    write_legacy_objects(writer, endian, &level.objects)
+                })?;
+                write_references(writer, endian, &level.destroyed_actors_after_objects)?;
+            }
+            write_references(writer, endian, &self.unresolved_destroyed_actors)
+        })
+    }
+
+    fn write_update8<W: Write + Seek>(&self, writer: &mut W, endian: Endian) -> BinResult<()> {
+        let persistent_level = self.persistent_level_for_writing(writer)?;
+        write_size_prefixed::<i64, _>(writer, endian, |writer| {
+            match &self.validation_data {
+                Some(validation_data) => validation_data.write_options(writer, endian, ())?,
+                None => WorldPartitionValidationData {
+                    grids: Map(Vec::new()),
+                }
+                .write_options(writer, endian, ())?,
+            }
+
+            let sub_levels: Vec<_> = self.sub_levels().collect();
+            write_length::<i32, _>(writer, sub_levels.len())?.write_options(writer, endian, ())?;
+            for level in sub_levels {
+                level.name.write_options(writer, endian, ())?;
+                write_blobs(writer, endian, level)?;
+                write_references(writer, endian, &level.destroyed_actors)?;
+            }
+
+            write_blobs(writer, endian, persistent_level)?;
+            write_length::<i32, _>(writer, self.level_to_destroyed_actors.0.len())?.write_options(
+                writer,
+                endian,
+                (),
+            )?;
+            for (level, actors) in &self.level_to_destroyed_actors.0 {
+                level.write_options(writer, endian, ())?;
+                write_references(writer, endian, actors)?;
+            }
+
+            write_references(writer, endian, &self.unresolved_destroyed_actors)
+        })
+    }
+
+    fn sub_levels(&self) -> impl Iterator<Item = &LevelData> {
+        self.levels.iter().filter(|level| level.name.is_some())
+    }
+
+    fn persistent_level_for_writing<W: Seek>(&self, writer: &mut W) -> BinResult<&LevelData> {
+        match self.persistent_level() {
+            Some(level) => Ok(level),
+            None => Err(binrw::Error::AssertFail {
+                pos: writer.stream_position()?,
+                message: "save data has no persistent level".to_owned(),
+            }),
+        }
+    }
+}
+
+/// Writes the headers of the objects of a level from before Update 8, which are stored like TOC
+/// headers.
+fn write_headers<W: Write + Seek>(
+    writer: &mut W,
+    endian: Endian,
+    objects: &[SaveObject],
+) -> BinResult<()> {
+    write_length::<i32, _>(writer, objects.len())?.write_options(writer, endian, ())?;
+    for object in objects {
+        object.header.write_options(writer, endian, ())?;
+    }
+    Ok(())
+}
+
+/// Writes the object count and the objects of a level from before Update 8, which have no GUID.
+fn write_legacy_objects<W: Write + Seek>(
+    writer: &mut W,
+    endian: Endian,
+    objects: &[SaveObject],
+) -> BinResult<()> {
+    write_length::<i32, _>(writer, objects.len())?.write_options(writer, endian, ())?;
+    for object in objects {
+        write_size_prefixed::<i32, _>(writer, endian, |writer| {
+            object
+                .data
+                .actor_references
+                .write_options(writer, endian, ())?;
+            object
+                .data
+                .properties
+                .write_options(writer, endian, (false,))?;
+            object.data.trailing.write_options(writer, endian, ())
+        })?;
+    }
+    Ok(())
+}
+
+fn write_blobs<W: Write + Seek>(
+    writer: &mut W,
+    endian: Endian,
+    level: &LevelData,
+) -> BinResult<()> {
+    write_toc_blob(
+        writer,
+        endian,
+        level.objects.iter().map(|object| &object.header),
+        level.toc_destroyed_actors.as_deref(),
+    )?;
+    let objects = level.objects.iter().map(|object| {
+        (
+            object.save_version,
+            object.should_migrate_object_refs_to_persistent,
+            &object.data,
+        )
+    });
+    write_data_blob(writer, endian, objects, &level.trailing, (true,))
+}
+
+fn write_references<W: Write + Seek>(
+    writer: &mut W,
+    endian: Endian,
+    references: &[ObjectReferenceDisc],
+) -> BinResult<()> {
+    write_length::<i32, _>(writer, references.len())?.write_options(writer, endian, ())?;
+    references.write_options(writer, endian, ())
+}
+
+impl SaveData {
+    fn from_legacy(body: LegacySaveFileBody, save_version: i32) -> Result<Self, Error> {
+        let level = LevelData {
+            name: None,
+            objects: legacy_objects(body.object_headers.0, body.objects, save_version)?,
+            destroyed_actors: Vec::new(),
+            toc_destroyed_actors: None,
+            trailing: Vec::new(),
+            objects_size: None,
+            destroyed_actors_after_objects: Vec::new(),
+        };
+
+        Ok(SaveData {
+            levels: vec![level],
+            validation_data: None,
+            level_to_destroyed_actors: Map(Default::default()),
+            unresolved_destroyed_actors: body
+                .collected_objects
+                .0
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn from_update6(body: Update6SaveFileBody, save_version: i32) -> Result<Self, Error> {
+        let levels = body
+            .sub_levels
+            .0
+            .into_iter()
+            .chain([body.persistent_level])
+            .map(|level| level_from_update6(level, save_version))
+            .collect::<Result<_, _>>()?;
+
+        Ok(SaveData {
+            levels,
+            validation_data: None,
+            level_to_destroyed_actors: Map(Default::default()),
+            unresolved_destroyed_actors: body
+                .object_references
+                .0
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// Fails if the TOC headers of a level don't match its data blob objects.
+impl TryFrom<SaveFileBody> for SaveData {
+    type Error = Error;
+
+    fn try_from(body: SaveFileBody) -> Result<Self, Error> {
+        let persistent = body.persistent_and_runtime_data;
+
+        let mut levels: Vec<_> = body
+            .per_level_data
+            .0
+            .into_iter()
+            .map(|(name, level)| {
+                level_from_blobs(
+                    Some(name),
+                    level.toc_blob,
+                    level.data_blob,
+                    level.destroyed_actors.0,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+
+        levels.push(level_from_blobs(
+            None,
+            persistent.toc_blob,
+            persistent.data_blob,
+            Vec::new(),
+        )?);
+
+        Ok(SaveData {
+            levels,
+            validation_data: Some(body.validation_data),
+            level_to_destroyed_actors: Map(persistent
+                .level_to_destroyed_actors
+                .0
+                .into_iter()
+                .map(|(level, actors)| (level, actors.0))
+                .collect()),
+            unresolved_destroyed_actors: body.unresolved_world_save_data.destroyed_actors.0,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// Pairs up TOC headers with their data.
+fn level_from_blobs(
+    name: Option<String>,
+    toc_blob: TocBlob,
+    data_blob: DataBlob,
+    destroyed_actors: Vec<ObjectReferenceDisc>,
+) -> Result<LevelData, Error> {
+    check_count(
+        "data blob object",
+        toc_blob.headers.len(),
+        data_blob.objects.len() as i64,
+    )?;
+    let objects = toc_blob
+        .headers
+        .into_iter()
+        .zip(data_blob.objects)
+        .map(|(header, object)| SaveObject {
+            header,
+            save_version: object.save_version,
+            should_migrate_object_refs_to_persistent: object
+                .should_migrate_object_refs_to_persistent,
+            data: object.data,
+        })
+        .collect();
+
+    Ok(LevelData {
+        name,
+        objects,
+        destroyed_actors,
+        toc_destroyed_actors: toc_blob.destroyed_actors.map(|actors| actors.0),
+        trailing: data_blob.trailing,
+        objects_size: None,
+        destroyed_actors_after_objects: Vec::new(),
+    })
+}
+
+fn level_from_update6(level: Level, save_version: i32) -> Result<LevelData, Error> {
+    Ok(LevelData {
+        name: level.sublevel_name,
+        objects: legacy_objects(level.object_headers.0, level.objects, save_version)?,
+        destroyed_actors: level.collectables.0.into_iter().map(Into::into).collect(),
+        toc_destroyed_actors: None,
+        trailing: Vec::new(),
+        objects_size: Some(level.objects_size),
+        destroyed_actors_after_objects: level.collections_2.0.into_iter().map(Into::into).collect(),
+    })
+}
+
+fn legacy_objects(
+    headers: Vec<ObjectHeader>,
+    objects: Vec<Object>,
+    save_version: i32,
+) -> Result<Vec<SaveObject>, Error> {
+    check_count("object", headers.len(), objects.len() as i64)?;
+    Ok(headers
+        .into_iter()
+        .zip(objects)
+        .map(|(header, object)| SaveObject {
+            header: header.into(),
+            save_version,
+            should_migrate_object_refs_to_persistent: false,
+            data: object.into(),
+        })
+        .collect())
+}
+
+/// Fails unless there are as many objects as headers describing them.
+fn check_count(what: &'static str, headers: usize, objects: i64) -> Result<(), Error> {
+    if i64::try_from(headers).is_ok_and(|headers| headers == objects) {
+        Ok(())
+    } else {
+        Err(Error::CountMismatch {
+            what,
+            expected: headers as i64,
+            actual: objects,
+        })
+    }
+}
+
+impl From<ObjectHeader> for GenericObjectSaveHeader {
+    fn from(header: ObjectHeader) -> Self {
+        match header {
+            ObjectHeader::Actor(header) => GenericObjectSaveHeader::Actor(ActorSaveHeader {
+                object_header: ObjectBaseSaveHeader {
+                    class_name: header.type_path,
+                    reference: ObjectReferenceDisc {
+                        level_name: header.root_object,
+                        path_name: header.instance_name,
+                    },
+                },
+                need_transform: header.need_transform != 0,
+                transform: Transform {
+                    rotation: [
+                        header.rotation_x,
+                        header.rotation_y,
+                        header.rotation_z,
+                        header.rotation_w,
+                    ],
+                    translation: [header.position_x, header.position_y, header.position_z],
+                    scale: [header.scale_x, header.scale_y, header.scale_z],
+                },
+                was_placed_in_level: header.was_placed_in_level != 0,
+            }),
+            ObjectHeader::Component(header) => GenericObjectSaveHeader::Object(ObjectSaveHeader {
+                base_header: ObjectBaseSaveHeader {
+                    class_name: header.type_path,
+                    reference: ObjectReferenceDisc {
+                        level_name: header.root_object,
+                        path_name: header.instance_name,
+                    },
+                },
+                outer_path_name: header.parent_actor_name,
+            }),
+        }
+    }
+}
+
+impl From<Object> for ObjectData {
+    fn from(object: Object) -> Self {
+        match object {
+            Object::Actor(actor) => ObjectData {
+                actor_references: Some(ActorReferences {
+                    parent: ObjectReferenceDisc {
+                        level_name: actor.parent_object_root,
+                        path_name: actor.parent_object_name,
+                    },
+                    children: actor
+                        .components
+                        .0
+                        .into_iter()
+                        .map(Into::into)
+                        .collect::<Vec<_>>()
+                        .into(),
+                }),
+                properties: actor.properties,
+                guid: None,
+                trailing: actor.trailing,
+            },
+            Object::Component(component) => ObjectData {
+                actor_references: None,
+                properties: component.properties,
+                guid: None,
+                trailing: component.trailing,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::{BinReaderExt, BinWrite};
+
+    use super::*;
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as i32 + 1).to_le_bytes().to_vec();
+        data.extend(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    fn size_prefixed_i32(data: Vec<u8>) -> Vec<u8> {
+        let mut prefixed = (data.len() as i32).to_le_bytes().to_vec();
+        prefixed.extend(data);
+        prefixed
+    }
+
+    fn size_prefixed_i64(data: Vec<u8>) -> Vec<u8> {
+        let mut prefixed = (data.len() as i64).to_le_bytes().to_vec();
+        prefixed.extend(data);
+        prefixed
+    }
+
+    fn references(references: &[(&str, &str)]) -> Vec<u8> {
+        let mut data = (references.len() as i32).to_le_bytes().to_vec();
+        for (level_name, path_name) in references {
+            data.extend(string(level_name));
+            data.extend(string(path_name));
+        }
+        data
+    }
+
+    /// An actor header, which is stored the same way in TOC blobs and before Update 8.
+    fn actor_header(instance_name: &str) -> Vec<u8> {
+        let mut data = 1i32.to_le_bytes().to_vec();
+        data.extend(string(
+            "/Game/FactoryGame/Buildable/Build_Foundation.Build_Foundation_C",
+        ));
+        data.extend(string("Persistent_Level"));
+        data.extend(string(instance_name));
+        data.extend(1i32.to_le_bytes());
+        for value in [0f32, 0.0, 0.0, 1.0, 100.0, 200.0, 300.0, 1.0, 1.0, 1.0] {
+            data.extend(value.to_le_bytes());
+        }
+        data.extend(0i32.to_le_bytes());
+        data
+    }
+
+    fn component_header(instance_name: &str, parent_actor_name: &str) -> Vec<u8> {
+        let mut data = 0i32.to_le_bytes().to_vec();
+        data.extend(string("/Script/FactoryGame.FGInventoryComponent"));
+        data.extend(string("Persistent_Level"));
+        data.extend(string(instance_name));
+        data.extend(string(parent_actor_name));
+        data
+    }
+
+    /// The properties of an object, with a single int property.
+    fn properties() -> Vec<u8> {
+        let mut data = string("mHealth");
+        data.extend(string("IntProperty"));
+        data.extend(4i32.to_le_bytes());
+        data.extend(0i32.to_le_bytes());
+        data.push(0);
+        data.extend(100i32.to_le_bytes());
+        data.extend(string("None"));
+        data
+    }
+
+    /// The data of an object, with the parent and components of actors and the GUID that objects
+    /// have since Update 8, followed by `trailing`.
+    fn object(
+        components: Option<&[(&str, &str)]>,
+        guid: Option<[u8; 16]>,
+        trailing: &[u8],
+    ) -> Vec<u8> {
+        let mut data = Vec::new();
+        if let Some(components) = components {
+            data.extend(string(""));
+            data.extend(string(""));
+            data.extend(references(components));
+        }
+        data.extend(properties());
+        if let Some(guid) = guid {
+            data.extend(1u32.to_le_bytes());
+            data.extend(guid);
+        }
+        data.extend(trailing);
+        size_prefixed_i32(data)
+    }
+
+    /// The headers and objects of a level with an actor and its component, from before Update 8.
+    fn headers_and_objects(actor: &str) -> (Vec<u8>, Vec<u8>) {
+        let component = format!("{actor}.Inventory");
+        let mut headers = 2i32.to_le_bytes().to_vec();
+        headers.extend(actor_header(actor));
+        headers.extend(component_header(&component, actor));
+        let mut objects = object(Some(&[("Persistent_Level", &component)]), None, &[0; 4]);
+        objects.extend(object(None, None, &[0; 4]));
+        (headers, objects)
+    }
+
+    fn legacy_body(object_count: i32) -> Vec<u8> {
+        let (headers, objects) = headers_and_objects("Persistent_Level:PersistentLevel.Foundation");
+        let mut data = headers;
+        data.extend(object_count.to_le_bytes());
+        data.extend(objects);
+        data.extend(references(&[(
+            "Persistent_Level",
+            "Persistent_Level:PersistentLevel.Nut",
+        )]));
+        size_prefixed_i32(data)
+    }
+
+    fn update6_level(name: Option<&str>, actor: &str) -> Vec<u8> {
+        let (headers, objects) = headers_and_objects(actor);
+        let mut data = name.map(string).unwrap_or_default();
+        let mut headers_and_collectables = headers;
+        headers_and_collectables.extend(references(&[(
+            "Persistent_Level",
+            "Persistent_Level:PersistentLevel.Nut",
+        )]));
+        data.extend(size_prefixed_i32(headers_and_collectables));
+        let mut counted_objects = 2i32.to_le_bytes().to_vec();
+        counted_objects.extend(objects);
+        data.extend(size_prefixed_i32(counted_objects));
+        data.extend(references(&[
+            ("Persistent_Level", "Persistent_Level:PersistentLevel.Nut"),
+            ("Persistent_Level", "Persistent_Level:PersistentLevel.Berry"),
+        ]));
+        data
+    }
+
+    fn update6_body() -> Vec<u8> {
+        let mut data = 1i32.to_le_bytes().to_vec();
+        data.extend(update6_level(
+            Some("Level_1"),
+            "Level_1:PersistentLevel.Foundation",
+        ));
+        data.extend(update6_level(
+            None,
+            "Persistent_Level:PersistentLevel.Foundation",
+        ));
+        data.extend(references(&[]));
+        size_prefixed_i64(data)
+    }
+
+    /// The TOC and data blob of a level with an actor and its component since Update 8.
+    fn blobs(actor: &str, destroyed_actors: Option<&[(&str, &str)]>) -> Vec<u8> {
+        let component = format!("{actor}.Inventory");
+        let mut toc_blob = 2i32.to_le_bytes().to_vec();
+        toc_blob.extend(actor_header(actor));
+        toc_blob.extend(component_header(&component, actor));
+        if let Some(destroyed_actors) = destroyed_actors {
+            toc_blob.extend(references(destroyed_actors));
+        }
+
+        let mut data_blob = 2i32.to_le_bytes().to_vec();
+        data_blob.extend(46i32.to_le_bytes());
+        data_blob.extend(0u32.to_le_bytes());
+        data_blob.extend(object(
+            Some(&[("Persistent_Level", &component)]),
+            Some([7; 16]),
+            &[1, 2, 3],
+        ));
+        data_blob.extend(46i32.to_le_bytes());
+        data_blob.extend(1u32.to_le_bytes());
+        data_blob.extend(object(None, None, &[0; 4]));
+        data_blob.extend([0xfe; 5]);
+
+        let mut data = size_prefixed_i64(toc_blob);
+        data.extend(size_prefixed_i64(data_blob));
+        data
+    }
+
+    /// A body with two sub-levels of the same name and the same level listed twice in the
+    /// persistent level's destroyed actors.
+    fn update8_body() -> Vec<u8> {
+        let mut data = 1i32.to_le_bytes().to_vec();
+        data.extend(string("MainGrid"));
+        data.extend(25600i32.to_le_bytes());
+        data.extend(0x1234u32.to_le_bytes());
+        data.extend(1i32.to_le_bytes());
+        data.extend(string("L0_X0_Y0"));
+        data.extend(0x5678u32.to_le_bytes());
+
+        data.extend(2i32.to_le_bytes());
+        for destroyed_actors in [
+            None,
+            Some(&[("Level_1", "Level_1:PersistentLevel.Rock")][..]),
+        ] {
+            data.extend(string("Level_1"));
+            data.extend(blobs(
+                "Level_1:PersistentLevel.Foundation",
+                destroyed_actors,
+            ));
+            data.extend(references(&[("Level_1", "Level_1:PersistentLevel.Nut")]));
+        }
+
+        data.extend(blobs(
+            "Persistent_Level:PersistentLevel.Foundation",
+            Some(&[("Persistent_Level", "Persistent_Level:PersistentLevel.Rock")]),
+        ));
+        data.extend(2i32.to_le_bytes());
+        for path_name in [
+            "Level_2:PersistentLevel.Nut",
+            "Level_2:PersistentLevel.Berry",
+        ] {
+            data.extend(string("Level_2"));
+            data.extend(references(&[("Level_2", path_name)]));
+        }
+
+        data.extend(references(&[("Level_3", "Level_3:PersistentLevel.Nut")]));
+        size_prefixed_i64(data)
+    }
+
+    fn read(save_version: i32, lenient: bool, data: Vec<u8>) -> Result<SaveData, Error> {
+        let ctx = ReadContext::new(save_version).with_lenient(lenient);
+        Cursor::new(data)
+            .read_le_args::<SaveData>((&ctx,))
+            .map_err(Error::from)
+    }
+
+    /// Reads the body and checks that writing it gives back the same bytes.
+    fn round_trip(save_version: i32, data: Vec<u8>) -> SaveData {
+        let save = read(save_version, false, data.clone()).unwrap();
+        let mut written = Cursor::new(Vec::new());
+        save.write_le_args(&mut written, (save_version,)).unwrap();
+        assert_eq!(written.into_inner(), data);
+        save
+    }
+
+    fn assert_actor_and_component(level: &LevelData, actor: &str) {
+        let [actor_object, component_object] = &level.objects[..] else {
+            panic!("expected an actor and a component");
+        };
+        assert!(actor_object.header.is_actor());
+        assert_eq!(actor_object.header.base_header().reference.path_name, actor);
+        let transform = actor_object.header.transform().unwrap();
+        assert_eq!(transform.translation, [100.0, 200.0, 300.0]);
+        let references = actor_object.data.actor_references.as_ref().unwrap();
+        assert_eq!(references.children.0.len(), 1);
+        assert_eq!(actor_object.data.properties.0[0].tag.name, "mHealth");
+
+        assert!(!component_object.header.is_actor());
+        let GenericObjectSaveHeader::Object(header) = &component_object.header else {
+            panic!("expected a component header");
+        };
+        assert_eq!(header.outer_path_name, actor);
+        assert!(component_object.data.actor_references.is_none());
+    }
+
+    #[test]
+    fn legacy_save() {
+        let save = round_trip(save_version::PER_LEVEL_STREAMING - 1, legacy_body(2));
+
+        let [level] = &save.levels[..] else {
+            panic!("expected a single level");
+        };
+        assert!(level.name.is_none());
+        assert_actor_and_component(level, "Persistent_Level:PersistentLevel.Foundation");
+        assert_eq!(level.objects[0].data.trailing, [0; 4]);
+        assert!(level.objects_size.is_none());
+        assert_eq!(save.unresolved_destroyed_actors.len(), 1);
+        assert!(save.warnings.is_empty());
+    }
+
+    #[test]
+    fn legacy_object_count_mismatch() {
+        let error = read(save_version::PER_LEVEL_STREAMING - 1, false, legacy_body(3)).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::CountMismatch {
+                what: "object",
+                expected: 2,
+                actual: 3
+            }
+        ));
+
+        let save = read(save_version::PER_LEVEL_STREAMING - 1, true, legacy_body(3)).unwrap();
+        assert_eq!(save.levels[0].objects.len(), 2);
+        assert!(matches!(
+            &save.warnings[..],
+            [ParseWarning {
+                error: Error::CountMismatch { actual: 3, .. },
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn update6_save() {
+        let save = round_trip(save_version::PER_LEVEL_STREAMING, update6_body());
+
+        let [sub_level, persistent_level] = &save.levels[..] else {
+            panic!("expected a sub-level and the persistent level");
+        };
+        assert_eq!(*sub_level.name.as_ref().unwrap(), "Level_1");
+        assert_actor_and_component(sub_level, "Level_1:PersistentLevel.Foundation");
+        assert!(persistent_level.name.is_none());
+        assert_actor_and_component(
+            persistent_level,
+            "Persistent_Level:PersistentLevel.Foundation",
+        );
+        for level in &save.levels {
+            assert!(level.objects_size.is_some_and(|size| size > 0));
+            assert_eq!(level.destroyed_actors.len(), 1);
+            assert_eq!(level.destroyed_actors_after_objects.len(), 2);
+            assert_eq!(level.all_destroyed_actors().count(), 3);
+        }
+        assert!(save.unresolved_destroyed_actors.is_empty());
+    }
+
+    #[test]
+    fn update8_save() {
+        let save = round_trip(save_version::UNREAL_ENGINE_5 + 5, update8_body());
+
+        let [first, second, persistent_level] = &save.levels[..] else {
+            panic!("expected two sub-levels and the persistent level");
+        };
+        for level in [first, second] {
+            assert_eq!(*level.name.as_ref().unwrap(), "Level_1");
+            assert_actor_and_component(level, "Level_1:PersistentLevel.Foundation");
+            assert_eq!(level.destroyed_actors.len(), 1);
+        }
+        assert!(first.toc_destroyed_actors.is_none());
+        assert_eq!(second.toc_destroyed_actors.as_ref().unwrap().len(), 1);
+        assert_eq!(second.all_destroyed_actors().count(), 2);
+        assert_actor_and_component(
+            persistent_level,
+            "Persistent_Level:PersistentLevel.Foundation",
+        );
+        assert_eq!(persistent_level.trailing, [0xfe; 5]);
+
+        let actor = &persistent_level.objects[0];
+        assert_eq!(actor.save_version, 46);
+        assert!(actor.data.guid.is_some());
+        assert_eq!(actor.data.trailing, [1, 2, 3]);
+        assert!(persistent_level.objects[1].should_migrate_object_refs_to_persistent);
+
+        let levels: Vec<_> = save
+            .level_to_destroyed_actors
+            .0
+            .iter()
+            .map(|(level, _)| level.to_string())
+            .collect();
+        assert_eq!(levels, ["Level_2", "Level_2"]);
+        assert_eq!(save.unresolved_destroyed_actors.len(), 1);
+    }
+
+    #[test]
+    fn update8_body_round_trip() {
+        let data = update8_body();
+        let ctx = ReadContext::new(save_version::UNREAL_ENGINE_5);
+        let body = Cursor::new(&data)
+            .read_le_args::<SaveFileBody>((&ctx,))
+            .unwrap();
+        assert_eq!(body.per_level_data.0.len(), 2);
+        let mut written = Cursor::new(Vec::new());
+        body.write_le(&mut written).unwrap();
+        assert_eq!(written.into_inner(), data);
+    }
+
+    #[test]
+    fn legacy_bodies_round_trip() {
+        let ctx = ReadContext::new(save_version::PER_LEVEL_STREAMING - 1);
+        let data = legacy_body(2);
+        let body = Cursor::new(&data)
+            .read_le_args::<LegacySaveFileBody>((&ctx,))
+            .unwrap();
+        let mut written = Cursor::new(Vec::new());
+        body.write_le(&mut written).unwrap();
+        assert_eq!(written.into_inner(), data);
+
+        let ctx = ReadContext::new(save_version::PER_LEVEL_STREAMING);
+        let data = update6_body();
+        let body = Cursor::new(&data)
+            .read_le_args::<Update6SaveFileBody>((&ctx,))
+            .unwrap();
+        let mut written = Cursor::new(Vec::new());
+        body.write_le(&mut written).unwrap();
+        assert_eq!(written.into_inner(), data);
+    }
+
+    #[test]
+    fn headers_without_objects() {
+        let (headers, _) = headers_and_objects("Persistent_Level:PersistentLevel.Foundation");
+        let headers = Cursor::new(headers)
+            .read_le::<crate::types::array::Array<ObjectHeader>>()
+            .unwrap();
+        assert!(matches!(
+            legacy_objects(headers.0, Vec::new(), 0),
+            Err(Error::CountMismatch {
+                expected: 2,
+                actual: 0,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,622 @@
+use std::{
+    io::{Read, Seek, Write},
+    time::{Duration, SystemTime},
+};
+
+use binrw::{BinRead, BinResult, BinWrite, Endian};
+use md5::{Digest, Md5};
+
+use crate::{
+    adabool, date_time, save_header_version, to_adabool,
+    types::{md5_hash::MD5Hash, string::String},
+    Error, MapOptions, ModMetadata,
+};
+
+/// The uncompressed header at the start of every save file.
+/// Fields that were added to the header after its initial version are `None` when the save's
+/// `save_header_version` predates them.
+#[derive(Debug, BinRead)]
+#[br(little, map = |raw: RawSaveFileHeader| raw.into())]
+pub struct SaveFileHeader {
+    pub save_header_version: i32,
+    pub save_version: i32,
+    pub build_version: i32,
+    pub editor_object_version: Option<i32>,
+    pub identity: SaveIdentity,
+    pub timing: SaveTiming,
+    pub mode: SaveModeFlags,
+    pub integrity: SaveIntegrity,
+}
+
+/// Which session and map the save belongs to.
+#[derive(Debug)]
+pub struct SaveIdentity {
+    pub map_name: String,
+    pub map_options: String,
+    pub session_name: Option<String>,
+    pub session_visibility: Option<ESessionVisibility>,
+    pub save_identifier: Option<String>,
+}
+
+/// When the save was made and how long the session has been played.
+#[derive(Debug)]
+pub struct SaveTiming {
+    /// Unreal `FDateTime` ticks, see [`date_time`].
+    pub save_date_time: i64,
+    pub play_duration_seconds: Option<i32>,
+}
+
+/// How the session is played.
+#[derive(Debug)]
+pub struct SaveModeFlags {
+    pub is_modded_save: Option<bool>,
+    pub mod_metadata: Option<String>,
+    pub is_partitioned_world: Option<bool>,
+    pub is_creative_mode_enabled: Option<bool>,
+}
+
+/// Data for detecting modified or corrupted saves.
+#[derive(Debug)]
+pub struct SaveIntegrity {
+    /// The MD5 hash of every byte following the header.
+    pub md5_hash: Option<MD5Hash>,
+}
+
+/// The outcome of checking a save against [`SaveIntegrity::md5_hash`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashVerification {
+    Match,
+    Mismatch {
+        expected: [u8; 16],
+        actual: [u8; 16],
+    },
+    /// The save predates the hash or the game did not compute one.
+    NotPresent,
+}
+
+/// Who can join the session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ESessionVisibility {
+    Private,
+    FriendsOnly,
+    /// A value this version of the parser doesn't know about.
+    Unknown(i8),
+}
+
+impl From<i8> for ESessionVisibility {
+    fn from(value: i8) -> Self {
+        match value {
+            0 => ESessionVisibility::Private,
+            1 => ESessionVisibility::FriendsOnly,
+            value => ESessionVisibility::Unknown(value),
+        }
+    }
+}
+
+impl From<ESessionVisibility> for i8 {
+    fn from(value: ESessionVisibility) -> Self {
+        match value {
+            ESessionVisibility::Private => 0,
+            ESessionVisibility::FriendsOnly => 1,
+            ESessionVisibility::Unknown(value) => value,
+        }
+    }
+}
+
+impl ESessionVisibility {
+    /// Parses the name used in the `Visibility` map option, e.g. `SV_FriendsOnly`.
+    pub fn from_option_name(name: &str) -> Option<Self> {
+        match name {
+            "SV_Private" => Some(ESessionVisibility::Private),
+            "SV_FriendsOnly" => Some(ESessionVisibility::FriendsOnly),
+            _ => None,
+        }
+    }
+
+    pub fn option_name(self) -> Option<&'static str> {
+        match self {
+            ESessionVisibility::Private => Some("SV_Private"),
+            ESessionVisibility::FriendsOnly => Some("SV_FriendsOnly"),
+            ESessionVisibility::Unknown(_) => None,
+        }
+    }
+}
+
+impl SaveIdentity {
+    pub fn parse_map_options(&self) -> MapOptions {
+        MapOptions::parse(&self.map_options.to_string())
+    }
+
+    pub fn set_map_options(&mut self, options: &MapOptions) {
+        self.map_options = String::from(options.to_string().as_str());
+    }
+}
+
+impl SaveTiming {
+    /// When the save was made, or `None` if the platform can't represent the stored time.
+    pub fn saved_at(&self) -> Option<SystemTime> {
+        date_time::ticks_to_system_time(self.save_date_time)
+    }
+
+    pub fn set_saved_at(&mut self, time: SystemTime) {
+        self.save_date_time = date_time::system_time_to_ticks(time);
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn saved_at_chrono(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        date_time::ticks_to_chrono(self.save_date_time)
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn set_saved_at_chrono(&mut self, time: chrono::DateTime<chrono::Utc>) {
+        self.save_date_time = date_time::chrono_to_ticks(time);
+    }
+
+    #[cfg(feature = "time")]
+    pub fn saved_at_offset_date_time(&self) -> Option<time::OffsetDateTime> {
+        date_time::ticks_to_offset_date_time(self.save_date_time)
+    }
+
+    #[cfg(feature = "time")]
+    pub fn set_saved_at_offset_date_time(&mut self, time: time::OffsetDateTime) {
+        self.save_date_time = date_time::offset_date_time_to_ticks(time);
+    }
+
+    /// The total time played in the session, or `None` for saves from before this was recorded.
+    pub fn play_duration(&self) -> Option<Duration> {
+        let seconds = u64::try_from(self.play_duration_seconds?).ok()?;
+        Some(Duration::from_secs(seconds))
+    }
+
+    /// Sets the play duration in whole seconds, saturating at the range of the stored `i32`.
+    pub fn set_play_duration(&mut self, duration: Duration) {
+        let seconds = i32::try_from(duration.as_secs()).unwrap_or(i32::MAX);
+        self.play_duration_seconds = Some(seconds);
+    }
+}
+
+impl SaveIntegrity {
+    /// Hashes everything `reader` yields, which must be the rest of the save file after the header,
+    /// and compares it to the stored hash.
+    pub fn verify(&self, mut reader: impl Read) -> Result<HashVerification, Error> {
+        let Some(expected) = self.md5_hash.and_then(|hash| hash.valid_bytes()) else {
+            return Ok(HashVerification::NotPresent);
+        };
+
+        let mut hasher = Md5::new();
+        std::io::copy(&mut reader, &mut hasher).map_err(Error::Io)?;
+        let actual: [u8; 16] = hasher.finalize().into();
+
+        Ok(if actual == expected {
+            HashVerification::Match
+        } else {
+            HashVerification::Mismatch { expected, actual }
+        })
+    }
+}
+
+impl SaveModeFlags {
+    /// Whether the save was made with mods; `false` for saves from before this was recorded.
+    pub fn is_modded(&self) -> bool {
+        self.is_modded_save.unwrap_or(false)
+    }
+
+    /// Whether creative mode was enabled; `false` for saves from before this was recorded.
+    pub fn is_creative(&self) -> bool {
+        self.is_creative_mode_enabled.unwrap_or(false)
+    }
+
+    /// Parses the mod metadata, returning `None` if the save has none.
+    pub fn parse_mod_metadata(&self) -> Result<Option<ModMetadata>, Error> {
+        match &self.mod_metadata {
+            Some(json) if *json != "" => ModMetadata::parse(&json.to_string()).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Writes the fields that the header's `save_header_version` has, so a field that is `None` but
+/// should be present is written with its default value.
+/// The MD5 hash is written as it is and has to be updated if the rest of the save changes.
+impl BinWrite for SaveFileHeader {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        RawSaveFileHeader::from(self).write_options(writer, endian, ())
+    }
+}
+
+/// The header fields in the order they are stored.
+#[derive(BinRead, BinWrite)]
+#[brw(little)]
+struct RawSaveFileHeader {
+    #[br(assert(
+        save_header_version <= save_header_version::LATEST,
+        "unsupported save header version {}",
+        save_header_version
+    ))]
+    save_header_version: i32,
+    save_version: i32,
+    build_version: i32,
+    map_name: String,
+    map_options: String,
+    #[br(if(save_header_version >= save_header_version::ADDED_SESSION_ID))]
+    session_name: Option<String>,
+    #[br(if(save_header_version >= save_header_version::ADDED_PLAY_DURATION))]
+    play_duration_seconds: Option<i32>,
+    save_date_time: i64,
+    #[br(if(save_header_version >= save_header_version::ADDED_SESSION_VISIBILITY))]
+    #[br(map = |value: Option<i8>| value.map(ESessionVisibility::from))]
+    #[bw(map = |value: &Option<ESessionVisibility>| value.map(i8::from))]
+    session_visibility: Option<ESessionVisibility>,
+    #[br(if(save_header_version >= save_header_version::UE425_ENGINE_UPDATE))]
+    editor_object_version: Option<i32>,
+    #[br(if(save_header_version >= save_header_version::ADDED_MODDING_PARAMS))]
+    mod_metadata: Option<String>,
+    #[br(if(save_header_version >= save_header_version::ADDED_MODDING_PARAMS))]
+    #[br(map = |value: Option<u32>| value.map(adabool))]
+    #[bw(map = |value: &Option<bool>| value.as_ref().map(to_adabool))]
+    is_modded_save: Option<bool>,
+    #[br(if(save_header_version >= save_header_version::ADDED_SAVE_IDENTIFIER))]
+    save_identifier: Option<String>,
+    #[br(if(save_header_version >= save_header_version::ADDED_IS_PARTITIONED_WORLD))]
+    #[br(map = |value: Option<u32>| value.map(adabool))]
+    #[bw(map = |value: &Option<bool>| value.as_ref().map(to_adabool))]
+    is_partitioned_world: Option<bool>,
+    #[br(if(save_header_version >= save_header_version::ADDED_SAVE_MODIFICATION_CHECKSUM))]
+    md5_hash: Option<MD5Hash>,
+    #[br(if(save_header_version >= save_header_version::ADDED_IS_CREATIVE_MODE_ENABLED))]
+    #[br(map = |value: Option<u32>| value.map(adabool))]
+    #[bw(map = |value: &Option<bool>| value.as_ref().map(to_adabool))]
+    is_creative_mode_enabled: Option<bool>,
+}
+
+impl From<RawSaveFileHeader> for SaveFileHeader {
+    fn from(raw: RawSaveFileHeader) -> Self {
+        SaveFileHeader {
+            save_header_version: raw.save_header_version,
+            save_version: raw.save_version,
+            build_version: raw.build_version,
+            editor_object_version: raw.editor_object_version,
+            identity: SaveIdentity {
+                map_name: raw.map_name,
+                map_options: raw.map_options,
+                session_name: raw.session_name,
+                session_visibility: raw.session_visibility,
+                save_identifier: raw.save_identifier,
+            },
+            timing: SaveTiming {
+                save_date_time: raw.save_date_time,
+                play_duration_seconds: raw.play_duration_seconds,
+            },
+            mode: SaveModeFlags {
+                is_modded_save: raw.is_modded_save,
+                mod_metadata: raw.mod_metadata,
+                is_partitioned_world: raw.is_partitioned_world,
+                is_creative_mode_enabled: raw.is_creative_mode_enabled,
+            },
+            integrity: SaveIntegrity {
+                md5_hash: raw.md5_hash,
+            },
+        }
+    }
+}
+
+impl From<&SaveFileHeader> for RawSaveFileHeader {
+    fn from(header: &SaveFileHeader) -> Self {
+        let version = header.save_header_version;
+        RawSaveFileHeader {
+            save_header_version: version,
+            save_version: header.save_version,
+            build_version: header.build_version,
+            map_name: header.identity.map_name.clone(),
+            map_options: header.identity.map_options.clone(),
+            session_name: since(
+                version,
+                save_header_version::ADDED_SESSION_ID,
+                header.identity.session_name.clone(),
+            ),
+            play_duration_seconds: since(
+                version,
+                save_header_version::ADDED_PLAY_DURATION,
+                header.timing.play_duration_seconds,
+            ),
+            save_date_time: header.timing.save_date_time,
+            session_visibility: (version >= save_header_version::ADDED_SESSION_VISIBILITY).then(
+                || {
+                    header
+                        .identity
+                        .session_visibility
+                        .unwrap_or(ESessionVisibility::Private)
+                },
+            ),
+            editor_object_version: since(
+                version,
+                save_header_version::UE425_ENGINE_UPDATE,
+                header.editor_object_version,
+            ),
+            mod_metadata: since(
+                version,
+                save_header_version::ADDED_MODDING_PARAMS,
+                header.mode.mod_metadata.clone(),
+            ),
+            is_modded_save: since(
+                version,
+                save_header_version::ADDED_MODDING_PARAMS,
+                header.mode.is_modded_save,
+            ),
+            save_identifier: since(
+                version,
+                save_header_version::ADDED_SAVE_IDENTIFIER,
+                header.identity.save_identifier.clone(),
+            ),
+            is_partitioned_world: since(
+                version,
+                save_header_version::ADDED_IS_PARTITIONED_WORLD,
+                header.mode.is_partitioned_world,
+            ),
+            md5_hash: since(
+                version,
+                save_header_version::ADDED_SAVE_MODIFICATION_CHECKSUM,
+                header.integrity.md5_hash,
+            ),
+            is_creative_mode_enabled: since(
+                version,
+                save_header_version::ADDED_IS_CREATIVE_MODE_ENABLED,
+                header.mode.is_creative_mode_enabled,
+            ),
+        }
+    }
+}
+
+/// The value of a field that was added in header version `added_in`, or `None` if the header
+/// predates it.
+fn since<T: Default>(save_header_version: i32, added_in: i32, value: Option<T>) -> Option<T> {
+    (save_header_version >= added_in).then(|| value.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::{BinReaderExt, BinWrite};
+
+    use super::*;
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as i32 + 1).to_le_bytes().to_vec();
+        data.extend(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    /// A header with every field of `save_header_version`.
+    fn header(save_header_version: i32) -> Vec<u8> {
+        let mut data = save_header_version.to_le_bytes().to_vec();
+        data.extend(46i32.to_le_bytes());
+        data.extend(365306i32.to_le_bytes());
+        data.extend(string("Persistent_Level"));
+        data.extend(string(
+            "?startloc=Grass Fields?sessionName=Factory?Visibility=SV_Private",
+        ));
+        if save_header_version >= save_header_version::ADDED_SESSION_ID {
+            data.extend(string("Factory"));
+        }
+        if save_header_version >= save_header_version::ADDED_PLAY_DURATION {
+            data.extend(3600i32.to_le_bytes());
+        }
+        data.extend(638_000_000_000_000_000i64.to_le_bytes());
+        if save_header_version >= save_header_version::ADDED_SESSION_VISIBILITY {
+            data.push(1);
+        }
+        if save_header_version >= save_header_version::UE425_ENGINE_UPDATE {
+            data.extend(40i32.to_le_bytes());
+        }
+        if save_header_version >= save_header_version::ADDED_MODDING_PARAMS {
+            data.extend(string(r#"{"Version":1,"FullMapName":"","Mods":[]}"#));
+            data.extend(1u32.to_le_bytes());
+        }
+        if save_header_version >= save_header_version::ADDED_SAVE_IDENTIFIER {
+            data.extend(string("A1B2C3"));
+        }
+        if save_header_version >= save_header_version::ADDED_IS_PARTITIONED_WORLD {
+            data.extend(1u32.to_le_bytes());
+        }
+        if save_header_version >= save_header_version::ADDED_SAVE_MODIFICATION_CHECKSUM {
+            data.extend(1u32.to_le_bytes());
+            data.extend([0xab; 16]);
+        }
+        if save_header_version >= save_header_version::ADDED_IS_CREATIVE_MODE_ENABLED {
+            data.extend(0u32.to_le_bytes());
+        }
+        data
+    }
+
+    /// Reads the header and checks that writing it gives back the same bytes.
+    fn round_trip(data: Vec<u8>) -> SaveFileHeader {
+        let mut reader = Cursor::new(&data);
+        let header = reader.read_le::<SaveFileHeader>().unwrap();
+        assert_eq!(reader.position(), data.len() as u64);
+        let mut written = Cursor::new(Vec::new());
+        header.write_le(&mut written).unwrap();
+        assert_eq!(written.into_inner(), data);
+        header
+    }
+
+    #[test]
+    fn latest_header_round_trip() {
+        let header = round_trip(header(save_header_version::LATEST));
+        assert_eq!(header.identity.save_identifier.unwrap(), "A1B2C3");
+        assert_eq!(
+            header.integrity.md5_hash.unwrap().valid_bytes(),
+            Some([0xab; 16])
+        );
+        assert_eq!(header.mode.is_creative_mode_enabled, Some(false));
+    }
+
+    #[test]
+    fn every_header_version() {
+        use save_header_version::*;
+
+        for version in 1..=LATEST {
+            let header = round_trip(header(version));
+            assert_eq!(header.save_header_version, version);
+            assert_eq!(header.save_version, 46);
+            assert_eq!(header.build_version, 365306);
+            assert_eq!(header.identity.map_name, "Persistent_Level");
+            assert_eq!(header.timing.save_date_time, 638_000_000_000_000_000);
+
+            let has = |added_in| version >= added_in;
+            assert_eq!(
+                header.identity.session_name.is_some(),
+                has(ADDED_SESSION_ID)
+            );
+            assert_eq!(
+                header.timing.play_duration_seconds,
+                has(ADDED_PLAY_DURATION).then_some(3600)
+            );
+            assert_eq!(
+                header.identity.session_visibility,
+                has(ADDED_SESSION_VISIBILITY).then_some(ESessionVisibility::FriendsOnly)
+            );
+            assert_eq!(
+                header.editor_object_version,
+                has(UE425_ENGINE_UPDATE).then_some(40)
+            );
+            assert_eq!(
+                header.mode.is_modded_save,
+                has(ADDED_MODDING_PARAMS).then_some(true)
+            );
+            assert_eq!(
+                header.mode.mod_metadata.is_some(),
+                has(ADDED_MODDING_PARAMS)
+            );
+            assert_eq!(
+                header.identity.save_identifier.is_some(),
+                has(ADDED_SAVE_IDENTIFIER)
+            );
+            assert_eq!(
+                header.mode.is_partitioned_world,
+                has(ADDED_IS_PARTITIONED_WORLD).then_some(true)
+            );
+            assert_eq!(
+                header
+                    .integrity
+                    .md5_hash
+                    .and_then(|hash| hash.valid_bytes()),
+                has(ADDED_SAVE_MODIFICATION_CHECKSUM).then_some([0xab; 16])
+            );
+            assert_eq!(
+                header.mode.is_creative_mode_enabled,
+                has(ADDED_IS_CREATIVE_MODE_ENABLED).then_some(false)
+            );
+        }
+    }
+
+    #[test]
+    fn editor_object_version_gate() {
+        // version 6 ends after the session visibility, so what follows belongs to the body.
+        let mut data = header(save_header_version::LOOK_AT_THE_COMMENT);
+        let header_len = data.len() as u64;
+        data.extend(40i32.to_le_bytes());
+        let mut reader = Cursor::new(&data);
+        let version_6 = reader.read_le::<SaveFileHeader>().unwrap();
+        assert_eq!(version_6.editor_object_version, None);
+        assert_eq!(reader.position(), header_len);
+
+        let version_7 = round_trip(header(save_header_version::UE425_ENGINE_UPDATE));
+        assert_eq!(version_7.editor_object_version, Some(40));
+        assert_eq!(version_7.mode.is_modded_save, None);
+    }
+
+    /// The MD5 hash of `"abc"`.
+    const ABC_MD5: [u8; 16] = [
+        0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0, 0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1, 0x7f,
+        0x72,
+    ];
+
+    fn integrity(md5_hash: Option<MD5Hash>) -> SaveIntegrity {
+        SaveIntegrity { md5_hash }
+    }
+
+    #[test]
+    fn verify_matching_hash() {
+        let integrity = integrity(Some(MD5Hash {
+            is_valid: true,
+            bytes: ABC_MD5,
+        }));
+        assert_eq!(
+            integrity.verify(&b"abc"[..]).unwrap(),
+            HashVerification::Match
+        );
+    }
+
+    #[test]
+    fn verify_mismatched_hash() {
+        let integrity = integrity(Some(MD5Hash {
+            is_valid: true,
+            bytes: [0xab; 16],
+        }));
+        assert_eq!(
+            integrity.verify(&b"abc"[..]).unwrap(),
+            HashVerification::Mismatch {
+                expected: [0xab; 16],
+                actual: ABC_MD5,
+            }
+        );
+    }
+
+    #[test]
+    fn verify_missing_hash() {
+        // neither a header without the field nor one whose hash was not computed is checked.
+        for md5_hash in [
+            None,
+            Some(MD5Hash {
+                is_valid: false,
+                bytes: ABC_MD5,
+            }),
+        ] {
+            assert_eq!(
+                integrity(md5_hash).verify(&b"abc"[..]).unwrap(),
+                HashVerification::NotPresent
+            );
+        }
+    }
+
+    #[test]
+    fn verify_read_error() {
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+        }
+
+        let integrity = integrity(Some(MD5Hash {
+            is_valid: true,
+            bytes: ABC_MD5,
+        }));
+        assert!(matches!(integrity.verify(Failing), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn missing_fields_are_written_with_defaults() {
+        let mut header = round_trip(header(save_header_version::LATEST));
+        header.identity.session_name = None;
+        header.mode.is_creative_mode_enabled = None;
+        let mut written = Cursor::new(Vec::new());
+        header.write_le(&mut written).unwrap();
+
+        let written = Cursor::new(written.into_inner())
+            .read_le::<SaveFileHeader>()
+            .unwrap();
+        assert_eq!(written.identity.session_name.unwrap(), "");
+        assert_eq!(written.mode.is_creative_mode_enabled, Some(false));
+    }
+}
@@ -0,0 +1,17 @@
+//! Known values of [`SaveFileHeader::save_header_version`](crate::SaveFileHeader::save_header_version).
+//! Each version adds fields to the end of the header.
+//! The names follow `FSaveHeader::Type` from the game's `FGSaveManagerInterface.h`.
+
+pub const ADDED_SESSION_ID: i32 = 2;
+pub const ADDED_PLAY_DURATION: i32 = 3;
+pub const ADDED_SESSION_VISIBILITY: i32 = 5;
+pub const LOOK_AT_THE_COMMENT: i32 = 6;
+pub const UE425_ENGINE_UPDATE: i32 = 7;
+pub const ADDED_MODDING_PARAMS: i32 = 8;
+pub const ADDED_SAVE_IDENTIFIER: i32 = 10;
+pub const ADDED_IS_PARTITIONED_WORLD: i32 = 11;
+pub const ADDED_SAVE_MODIFICATION_CHECKSUM: i32 = 12;
+pub const ADDED_IS_CREATIVE_MODE_ENABLED: i32 = 13;
+
+/// The newest header version this crate knows how to read.
+pub const LATEST: i32 = ADDED_IS_CREATIVE_MODE_ENABLED;
@@ -0,0 +1,9 @@
+//! Known values of [`SaveFileHeader::save_version`](crate::SaveFileHeader::save_version).
+//! The names follow `FSaveCustomVersion` from the game's `FGSaveManagerInterface.h`.
+
+/// Update 6 split the body into per-level data.
+pub const PER_LEVEL_STREAMING: i32 = 29;
+
+/// Update 8 moved to Unreal Engine 5, world partition and 64-bit blob sizes.
+/// From here on, vectors, rotators and quaternions in struct properties are stored as doubles.
+pub const UNREAL_ENGINE_5: i32 = 41;
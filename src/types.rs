@@ -1,16 +1,31 @@
 pub mod actor_save_header;
 pub mod array;
+pub mod data_blob;
+pub mod format_argument_data;
+pub mod format_argument_value;
+pub mod generic_object_save_header;
 pub mod guid;
 pub mod map;
 pub mod md5_hash;
+pub mod number_formatting_options;
 pub mod object_base_save_header;
+pub mod object_data;
 pub mod object_reference_disc;
+pub mod object_save_data;
 pub mod object_save_header;
 pub mod per_streaming_level_save_data;
 pub mod persistent_and_runtime_save_data;
+pub mod property_tag;
+pub mod quatd;
+pub mod rotator;
+pub mod soft_object_path;
 pub mod string;
 pub mod text;
+pub mod toc_blob;
+pub mod top_level_asset_path;
 pub mod transform;
+pub mod unresolved_world_save_data;
+pub mod vector2d;
+pub mod vector3d;
 pub mod world_partition_validation_data;
 pub mod wp_grid_validation_data;
-pub mod unresolved_world_save_data;
\ No newline at end of file
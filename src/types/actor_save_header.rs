@@ -1,15 +1,17 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
-use crate::adabool;
+use crate::{adabool, to_adabool};
 
 use super::{object_base_save_header::ObjectBaseSaveHeader, transform::Transform};
 
-#[derive(BinRead)]
+#[derive(Debug, BinRead, BinWrite)]
 pub struct ActorSaveHeader {
     pub object_header: ObjectBaseSaveHeader,
     #[br(map = adabool)]
+    #[bw(map = to_adabool)]
     pub need_transform: bool,
     pub transform: Transform,
     #[br(map = adabool)]
+    #[bw(map = to_adabool)]
     pub was_placed_in_level: bool,
 }
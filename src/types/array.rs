@@ -1,9 +1,11 @@
 use std::{
-    io::{Read, Seek},
+    io::{Read, Seek, SeekFrom, Write},
     marker::PhantomData,
 };
 
-use binrw::{BinRead, BinResult, Endian, NamedArgs};
+use binrw::{BinRead, BinResult, BinWrite, Endian, NamedArgs};
+
+use crate::limits;
 
 #[derive(Clone, Default, NamedArgs)]
 pub struct ArrayArgs<Inner: Clone> {
@@ -11,8 +13,10 @@ pub struct ArrayArgs<Inner: Clone> {
     pub inner: Inner,
 }
 
-pub trait ArraySizeType {
-    fn into_usize(self) -> usize;
+pub trait ArraySizeType: Sized {
+    fn into_i64(self) -> i64;
+    /// Converts an element count back, or `None` if it does not fit.
+    fn from_len(len: usize) -> Option<Self>;
 }
 
 #[derive(Debug)]
@@ -31,20 +35,90 @@ where
         endian: Endian,
         args: Self::Args<'_>,
     ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
         let count = SizeType::read_options(reader, endian, ())?;
-        binrw::helpers::count_with(count.into_usize(), T::read_options)(reader, endian, args.inner)
+        let count = limits::check_array_length(pos, "array length", count.into_i64())?;
+        binrw::helpers::count_with(count, T::read_options)(reader, endian, args.inner)
             .map(|vec| Array(vec, PhantomData))
     }
 }
 
+impl<T, SizeType> BinWrite for Array<T, SizeType>
+where
+    T: BinWrite + 'static,
+    for<'a> T::Args<'a>: Clone,
+    SizeType: BinWrite<Args<'static> = ()> + ArraySizeType,
+{
+    type Args<'a> = ArrayArgs<T::Args<'a>>;
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let count = write_length::<SizeType, _>(writer, self.0.len())?;
+        count.write_options(writer, endian, ())?;
+        self.0.write_options(writer, endian, args.inner)
+    }
+}
+
+/// Converts the number of elements of something being written to its size type.
+pub(crate) fn write_length<SizeType: ArraySizeType, W: Seek>(
+    writer: &mut W,
+    len: usize,
+) -> BinResult<SizeType> {
+    SizeType::from_len(len).ok_or_else(|| binrw::Error::AssertFail {
+        pos: writer.stream_position().unwrap_or_default(),
+        message: format!("{len} elements do not fit the length field"),
+    })
+}
+
+/// Writes what `write` writes, prefixed by its size in bytes.
+pub(crate) fn write_size_prefixed<SizeType, W>(
+    writer: &mut W,
+    endian: Endian,
+    write: impl FnOnce(&mut W) -> BinResult<()>,
+) -> BinResult<()>
+where
+    SizeType: BinWrite<Args<'static> = ()> + ArraySizeType,
+    W: Write + Seek,
+{
+    let size_pos = writer.stream_position()?;
+    write_length::<SizeType, _>(writer, 0)?.write_options(writer, endian, ())?;
+    let start = writer.stream_position()?;
+    write(writer)?;
+    let end = writer.stream_position()?;
+
+    let size = write_length::<SizeType, _>(writer, (end - start) as usize)?;
+    writer.seek(SeekFrom::Start(size_pos))?;
+    size.write_options(writer, endian, ())?;
+    writer.seek(SeekFrom::Start(end))?;
+    Ok(())
+}
+
 impl ArraySizeType for i32 {
-    fn into_usize(self) -> usize {
-        self as usize
+    fn into_i64(self) -> i64 {
+        self.into()
+    }
+
+    fn from_len(len: usize) -> Option<Self> {
+        len.try_into().ok()
     }
 }
 
 impl ArraySizeType for i64 {
-    fn into_usize(self) -> usize {
-        self as usize
+    fn into_i64(self) -> i64 {
+        self
+    }
+
+    fn from_len(len: usize) -> Option<Self> {
+        len.try_into().ok()
+    }
+}
+
+impl<T, SizeType> From<Vec<T>> for Array<T, SizeType> {
+    fn from(vec: Vec<T>) -> Self {
+        Array(vec, PhantomData)
     }
 }
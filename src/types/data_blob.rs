@@ -0,0 +1,209 @@
+use std::io::{Read, Seek, Write};
+
+use binrw::{BinRead, BinResult, BinWrite, Endian};
+
+use crate::{limits, to_adabool, ReadContext};
+
+use super::{
+    array::{write_length, write_size_prefixed},
+    generic_object_save_header::GenericObjectSaveHeader,
+    object_data::{read_remaining, ObjectData},
+    object_save_data::ObjectSaveData,
+};
+
+/// The decoded contents of a `DataBlob64` buffer.
+/// Objects are stored in the same order as their headers in the corresponding
+/// [`TocBlob`](super::toc_blob::TocBlob), which is needed to know which of them are actors.
+#[derive(Debug)]
+pub struct DataBlob {
+    pub objects: Vec<ObjectSaveData>,
+    /// Data after the last object that was not decoded.
+    pub trailing: Vec<u8>,
+}
+
+impl BinRead for DataBlob {
+    type Args<'a> = (&'a [GenericObjectSaveHeader], &'a ReadContext);
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        (headers, ctx): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let size_pos = reader.stream_position()?;
+        let size = i64::read_options(reader, endian, ())?;
+        let start = reader.stream_position()?;
+        let end = start + limits::check_body_size(size_pos, "data blob size", size)?;
+
+        let count_pos = reader.stream_position()?;
+        let count = i32::read_options(reader, endian, ())?;
+        let count = limits::check_array_length(count_pos, "data blob object count", count.into())?;
+        ctx.check_count(count_pos, "data blob object", headers.len(), count as i64)?;
+
+        // objects that overran the blob are resynchronised back to its end.
+        let objects = ctx.pinned_for_resync(end, || {
+            headers
+                .iter()
+                .map(|header| {
+                    let offset = reader.stream_position()?;
+                    let instance_name = header.base_header().reference.path_name.to_string();
+                    ctx.pinned_for_resync(offset, || {
+                        ctx.in_object(&instance_name, offset, || {
+                            ObjectSaveData::read_options(
+                                reader,
+                                endian,
+                                (header.is_actor(), &header.base_header().class_name, ctx),
+                            )
+                        })
+                    })
+                })
+                .collect::<BinResult<Vec<_>>>()
+        })?;
+
+        let trailing = read_remaining(reader, "data blob", start, end, ctx)?;
+
+        Ok(DataBlob { objects, trailing })
+    }
+}
+
+/// Writes the objects and the data after them, so that writing a blob that was read gives back the
+/// same bytes. The `bool` argument is whether vectors are written with double precision.
+impl BinWrite for DataBlob {
+    type Args<'a> = (bool,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let objects = self.objects.iter().map(|object| {
+            (
+                object.save_version,
+                object.should_migrate_object_refs_to_persistent,
+                &object.data,
+            )
+        });
+        write_data_blob(writer, endian, objects, &self.trailing, args)
+    }
+}
+
+/// Writes a data blob prefixed by its size, from the save version, the
+/// `ShouldMigrateObjectRefsToPersistent` flag and the data of each object.
+pub(crate) fn write_data_blob<'a, W: Write + Seek>(
+    writer: &mut W,
+    endian: Endian,
+    objects: impl ExactSizeIterator<Item = (i32, bool, &'a ObjectData)>,
+    trailing: &[u8],
+    args: (bool,),
+) -> BinResult<()> {
+    write_size_prefixed::<i64, _>(writer, endian, |writer| {
+        write_length::<i32, _>(writer, objects.len())?.write_options(writer, endian, ())?;
+        for (save_version, should_migrate_object_refs_to_persistent, data) in objects {
+            save_version.write_options(writer, endian, ())?;
+            to_adabool(&should_migrate_object_refs_to_persistent).write_options(
+                writer,
+                endian,
+                (),
+            )?;
+            data.write_options(writer, endian, args)?;
+        }
+        trailing.write_options(writer, endian, ())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::BinReaderExt;
+
+    use super::*;
+    use crate::Error;
+
+    fn read(size: i64, count: i32) -> Error {
+        let mut data = size.to_le_bytes().to_vec();
+        data.extend(count.to_le_bytes());
+        let ctx = ReadContext::default();
+        Error::from(
+            Cursor::new(data)
+                .read_le_args::<DataBlob>((&[], &ctx))
+                .unwrap_err(),
+        )
+    }
+
+    #[test]
+    fn negative_size() {
+        assert!(matches!(
+            read(-4, 0),
+            Error::LimitExceeded { offset: 0, reason } if reason.requested == -4
+        ));
+    }
+
+    #[test]
+    fn negative_count() {
+        assert!(matches!(
+            read(4, -1),
+            Error::LimitExceeded { offset: 8, reason } if reason.requested == -1
+        ));
+    }
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as i32 + 1).to_le_bytes().to_vec();
+        data.extend(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    fn header(path_name: &str) -> GenericObjectSaveHeader {
+        let mut data = 0u32.to_le_bytes().to_vec();
+        data.extend(string("/Script/FactoryGame.FGInventoryComponent"));
+        data.extend(string("Persistent_Level"));
+        data.extend(string(path_name));
+        data.extend(string("Persistent_Level:PersistentLevel"));
+        Cursor::new(data).read_le().unwrap()
+    }
+
+    /// An object with a single int property followed by class-specific data.
+    fn object(trailing: &[u8]) -> Vec<u8> {
+        let mut properties = string("mHealth");
+        properties.extend(string("IntProperty"));
+        properties.extend(4i32.to_le_bytes());
+        properties.extend(0i32.to_le_bytes());
+        properties.push(0);
+        properties.extend(100i32.to_le_bytes());
+        properties.extend(string("None"));
+        properties.extend(0u32.to_le_bytes());
+        properties.extend(trailing);
+
+        let mut data = 46i32.to_le_bytes().to_vec();
+        data.extend(0u32.to_le_bytes());
+        data.extend((properties.len() as i32).to_le_bytes());
+        data.extend(properties);
+        data
+    }
+
+    #[test]
+    fn round_trip_keeps_trailing_bytes() {
+        let mut contents = 2i32.to_le_bytes().to_vec();
+        contents.extend(object(&[1, 2, 3]));
+        contents.extend(object(&[]));
+        contents.extend([9, 9]);
+        let mut data = (contents.len() as i64).to_le_bytes().to_vec();
+        data.extend(contents);
+
+        let headers = [header("Object_1"), header("Object_2")];
+        let ctx = ReadContext::new(46);
+        let blob = Cursor::new(&data)
+            .read_le_args::<DataBlob>((&headers, &ctx))
+            .unwrap();
+        assert_eq!(blob.objects[0].data.trailing, [1, 2, 3]);
+        assert!(blob.objects[1].data.trailing.is_empty());
+        assert_eq!(blob.trailing, [9, 9]);
+        assert!(ctx.take_warnings().is_empty());
+
+        let mut written = Cursor::new(Vec::new());
+        blob.write_le_args(&mut written, (ctx.is_double_precision(),))
+            .unwrap();
+        assert_eq!(written.into_inner(), data);
+    }
+}
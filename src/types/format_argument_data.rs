@@ -0,0 +1,12 @@
+use binrw::{BinRead, BinWrite};
+
+use super::{format_argument_value::FormatArgumentValue, string::String};
+
+/// A named argument of an [`ArgumentFormat`](super::text::TextHistoryType::ArgumentFormat) text.
+#[derive(Debug, BinRead, BinWrite)]
+#[br(import(depth: usize))]
+pub struct FormatArgumentData {
+    pub argument_name: String,
+    #[br(args(depth))]
+    pub value: FormatArgumentValue,
+}
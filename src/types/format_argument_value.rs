@@ -0,0 +1,37 @@
+use binrw::{BinRead, BinWrite};
+
+use super::text::{format_number, NumberFormat, Text};
+
+/// The value of an argument to a formatted [`Text`], prefixed by its `EFormatArgumentType`.
+#[derive(Debug, BinRead, BinWrite)]
+#[br(import(depth: usize))]
+pub enum FormatArgumentValue {
+    #[brw(magic = 0i8)]
+    Int(i64),
+    #[brw(magic = 1i8)]
+    UInt(u64),
+    #[brw(magic = 2i8)]
+    Float(f32),
+    #[brw(magic = 3i8)]
+    Double(f64),
+    #[brw(magic = 4i8)]
+    Text(#[br(args(depth + 1))] Box<Text>),
+    #[brw(magic = 5i8)]
+    Gender(u8),
+}
+
+impl FormatArgumentValue {
+    /// Renders the value the way it is substituted into a format pattern.
+    /// Genders only select between plural/gender forms and render as an empty string.
+    pub fn to_display_string(&self) -> std::string::String {
+        let format = NumberFormat::default();
+        match self {
+            FormatArgumentValue::Int(value) => format_number(*value as f64, &format),
+            FormatArgumentValue::UInt(value) => format_number(*value as f64, &format),
+            FormatArgumentValue::Float(value) => format_number(f64::from(*value), &format),
+            FormatArgumentValue::Double(value) => format_number(*value, &format),
+            FormatArgumentValue::Text(text) => text.to_display_string(),
+            FormatArgumentValue::Gender(_) => std::string::String::new(),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use binrw::{BinRead, BinWrite};
+
+use super::{
+    actor_save_header::ActorSaveHeader, object_base_save_header::ObjectBaseSaveHeader,
+    object_save_header::ObjectSaveHeader, transform::Transform,
+};
+
+/// A single TOC entry, discriminated by the `isActor` flag that precedes it.
+#[derive(Debug, BinRead, BinWrite)]
+pub enum GenericObjectSaveHeader {
+    #[brw(magic = 1u32)]
+    Actor(ActorSaveHeader),
+    #[brw(magic = 0u32)]
+    Object(ObjectSaveHeader),
+}
+
+impl GenericObjectSaveHeader {
+    pub fn is_actor(&self) -> bool {
+        matches!(self, GenericObjectSaveHeader::Actor(_))
+    }
+
+    pub fn base_header(&self) -> &ObjectBaseSaveHeader {
+        match self {
+            GenericObjectSaveHeader::Actor(header) => &header.object_header,
+            GenericObjectSaveHeader::Object(header) => &header.base_header,
+        }
+    }
+
+    /// The actor's transform, or `None` for non-actor objects.
+    pub fn transform(&self) -> Option<&Transform> {
+        match self {
+            GenericObjectSaveHeader::Actor(header) => Some(&header.transform),
+            GenericObjectSaveHeader::Object(_) => None,
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
-#[derive(BinRead)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, BinRead, BinWrite)]
 pub struct Guid {
     pub a: u32,
     pub b: u32,
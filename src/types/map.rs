@@ -1,12 +1,17 @@
-use binrw::BinRead;
-use indexmap::IndexMap;
-use std::hash::Hash;
+use binrw::{BinRead, BinWrite};
 
-pub struct Map<K, V>(pub IndexMap<K, V>);
+use crate::limits;
+
+use super::array::write_length;
+
+/// A `TMap`, with its entries in save order.
+/// Entries are kept as a list because saves can contain the same key more than once.
+#[derive(Debug)]
+pub struct Map<K, V>(pub Vec<(K, V)>);
 
 impl<K, V> BinRead for Map<K, V>
 where
-    K: Hash + Eq + BinRead<Args<'static> = ()>,
+    K: BinRead<Args<'static> = ()>,
     V: BinRead,
     for<'a> <V as BinRead>::Args<'a>: Clone,
 {
@@ -17,13 +22,38 @@ where
         endian: binrw::Endian,
         args: Self::Args<'_>,
     ) -> binrw::BinResult<Self> {
+        let pos = reader.stream_position()?;
         let size = i32::read_options(reader, endian, ())?;
-        let mut data = IndexMap::new();
+        let size = limits::check_array_length(pos, "map length", size.into())?;
+        let mut data = Vec::new();
         for _ in 0..size {
             let key = K::read_options(reader, endian, ())?;
             let value = V::read_options(reader, endian, args.clone())?;
-            data.insert(key, value);
+            data.push((key, value));
         }
         Ok(Map(data))
     }
 }
+
+impl<K, V> BinWrite for Map<K, V>
+where
+    K: BinWrite<Args<'static> = ()>,
+    V: BinWrite,
+    for<'a> <V as BinWrite>::Args<'a>: Clone,
+{
+    type Args<'a> = <V as BinWrite>::Args<'a>;
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        write_length::<i32, _>(writer, self.0.len())?.write_options(writer, endian, ())?;
+        for (key, value) in &self.0 {
+            key.write_options(writer, endian, ())?;
+            value.write_options(writer, endian, args.clone())?;
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,20 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
-use crate::adabool;
+use crate::{adabool, to_adabool};
 
-#[derive(BinRead)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, BinRead, BinWrite)]
 pub struct MD5Hash {
     #[br(map = adabool)]
+    #[bw(map = to_adabool)]
     pub is_valid: bool,
     #[br(if(is_valid))]
+    #[bw(if(is_valid != 0))]
     pub bytes: [u8; 16],
 }
+
+impl MD5Hash {
+    /// The hash bytes, or `None` if the game did not compute a hash.
+    pub fn valid_bytes(&self) -> Option<[u8; 16]> {
+        self.is_valid.then_some(self.bytes)
+    }
+}
@@ -0,0 +1,18 @@
+use binrw::{BinRead, BinWrite};
+
+use crate::{adabool, to_adabool};
+
+#[derive(Debug, BinRead, BinWrite)]
+pub struct NumberFormattingOptions {
+    #[br(map = adabool)]
+    #[bw(map = to_adabool)]
+    pub always_sign: bool,
+    #[br(map = adabool)]
+    #[bw(map = to_adabool)]
+    pub use_grouping: bool,
+    pub rounding_mode: i8,
+    pub minimum_integral_digits: i32,
+    pub maximum_integral_digits: i32,
+    pub minimum_fractional_digits: i32,
+    pub maximum_fractional_digits: i32,
+}
@@ -1,8 +1,8 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
 use super::{object_reference_disc::ObjectReferenceDisc, string::String};
 
-#[derive(BinRead)]
+#[derive(Debug, BinRead, BinWrite)]
 pub struct ObjectBaseSaveHeader {
     pub class_name: String,
     pub reference: ObjectReferenceDisc,
@@ -0,0 +1,130 @@
+use std::io::{Read, Seek, Write};
+
+use binrw::{BinRead, BinResult, BinWrite, Endian, VecArgs};
+
+use crate::{adabool, limits, to_adabool, PropertyList, ReadContext};
+
+use super::{
+    array::{write_size_prefixed, Array},
+    guid::Guid,
+    object_reference_disc::ObjectReferenceDisc,
+    string::String,
+};
+
+/// The decoded `Data` payload of an [`ObjectSaveData`](super::object_save_data::ObjectSaveData).
+/// The payload is prefixed by its int32 size; anything left over after the common object data is
+/// class-specific binary data and is kept in `trailing`.
+#[derive(Debug)]
+pub struct ObjectData {
+    pub actor_references: Option<ActorReferences>,
+    pub properties: PropertyList,
+    pub guid: Option<Guid>,
+    pub trailing: Vec<u8>,
+}
+
+/// Parent and child references, only present in the data of actors.
+#[derive(Debug, BinRead, BinWrite)]
+pub struct ActorReferences {
+    pub parent: ObjectReferenceDisc,
+    pub children: Array<ObjectReferenceDisc>,
+}
+
+impl BinRead for ObjectData {
+    type Args<'a> = (bool, &'a String, &'a ReadContext);
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        (is_actor, class, ctx): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let size = i32::read_options(reader, endian, ())?;
+        let start = reader.stream_position()?;
+
+        let actor_references = if is_actor {
+            Some(ActorReferences::read_options(reader, endian, ())?)
+        } else {
+            None
+        };
+        let properties = PropertyList::read_options(reader, endian, (Some(class), ctx))?;
+        let has_guid = adabool(u32::read_options(reader, endian, ())?);
+        let guid = if has_guid {
+            Some(Guid::read_options(reader, endian, ())?)
+        } else {
+            None
+        };
+
+        let trailing = read_trailing(reader, "object data", start, size, ctx)?;
+
+        Ok(ObjectData {
+            actor_references,
+            properties,
+            guid,
+            trailing,
+        })
+    }
+}
+
+/// Writes the data prefixed by its size, so it can be written back after the properties have
+/// changed. The `bool` argument is whether vectors are written with double precision.
+impl BinWrite for ObjectData {
+    type Args<'a> = (bool,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_size_prefixed::<i32, _>(writer, endian, |writer| {
+            self.actor_references.write_options(writer, endian, ())?;
+            self.properties.write_options(writer, endian, args)?;
+            to_adabool(&self.guid.is_some()).write_options(writer, endian, ())?;
+            self.guid.write_options(writer, endian, ())?;
+            self.trailing.write_options(writer, endian, ())
+        })
+    }
+}
+
+/// Reads the rest of an object that started at `start` and declared `size` bytes, which is
+/// class-specific binary data the decoder does not understand.
+pub(crate) fn read_trailing<R: Read + Seek>(
+    reader: &mut R,
+    what: &'static str,
+    start: u64,
+    size: i32,
+    ctx: &ReadContext,
+) -> BinResult<Vec<u8>> {
+    let end = start + limits::check_array_length(start, "object size", size.into())? as u64;
+    read_remaining(reader, what, start, end, ctx)
+}
+
+/// Reads everything from the current position up to `end`, where something that started at
+/// `start` is declared to end. Overrunning `end` is reported, or resynchronised, like any other
+/// size mismatch, and fails if the reader is still past `end` afterwards.
+pub(crate) fn read_remaining<R: Read + Seek>(
+    reader: &mut R,
+    what: &'static str,
+    start: u64,
+    end: u64,
+    ctx: &ReadContext,
+) -> BinResult<Vec<u8>> {
+    if reader.stream_position()? > end {
+        ctx.check_size(reader, what, start, (end - start) as i64)?;
+    }
+
+    let pos = reader.stream_position()?;
+    if pos > end {
+        return Err(binrw::Error::AssertFail {
+            pos,
+            message: format!("{what} overran its size, expected to end at {end}"),
+        });
+    }
+    Vec::<u8>::read_options(
+        reader,
+        Endian::Little,
+        VecArgs {
+            count: (end - pos) as usize,
+            inner: (),
+        },
+    )
+}
@@ -1,8 +1,8 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
 use super::string::String;
 
-#[derive(BinRead)]
+#[derive(Debug, BinRead, BinWrite)]
 pub struct ObjectReferenceDisc {
     pub level_name: String,
     pub path_name: String,
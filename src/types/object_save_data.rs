@@ -0,0 +1,18 @@
+use binrw::{BinRead, BinWrite};
+
+use crate::{adabool, to_adabool, ReadContext};
+
+use super::{object_data::ObjectData, string::String};
+
+#[derive(Debug, BinRead, BinWrite)]
+#[br(import(is_actor: bool, class: &String, ctx: &ReadContext))]
+#[bw(import(double_precision: bool))]
+pub struct ObjectSaveData {
+    pub save_version: i32,
+    #[br(map = adabool)]
+    #[bw(map = to_adabool)]
+    pub should_migrate_object_refs_to_persistent: bool,
+    #[br(args(is_actor, class, ctx))]
+    #[bw(args(double_precision))]
+    pub data: ObjectData,
+}
@@ -1,8 +1,8 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
 use super::{object_base_save_header::ObjectBaseSaveHeader, string::String};
 
-#[derive(BinRead)]
+#[derive(Debug, BinRead, BinWrite)]
 pub struct ObjectSaveHeader {
     pub base_header: ObjectBaseSaveHeader,
     pub outer_path_name: String,
@@ -1,10 +1,19 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
-use super::{array::Array, object_reference_disc::ObjectReferenceDisc};
+use crate::ReadContext;
 
-#[derive(BinRead)]
+use super::{
+    array::Array, data_blob::DataBlob, object_reference_disc::ObjectReferenceDisc,
+    toc_blob::TocBlob,
+};
+
+#[derive(Debug, BinRead, BinWrite)]
+#[br(import(ctx: &ReadContext))]
+#[bw(import(double_precision: bool))]
 pub struct PerStreamingLevelSaveData {
-    toc_blob: Array<u8, i64>,
-    data_blob: Array<u8, i64>,
-    destroyed_actors: Array<ObjectReferenceDisc>,
+    pub toc_blob: TocBlob,
+    #[br(args(&toc_blob.headers, ctx))]
+    #[bw(args(double_precision))]
+    pub data_blob: DataBlob,
+    pub destroyed_actors: Array<ObjectReferenceDisc>,
 }
@@ -1,10 +1,19 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
-use super::{array::Array, map::Map, object_reference_disc::ObjectReferenceDisc, string::String};
+use crate::ReadContext;
 
-#[derive(BinRead)]
+use super::{
+    array::Array, data_blob::DataBlob, map::Map, object_reference_disc::ObjectReferenceDisc,
+    string::String, toc_blob::TocBlob,
+};
+
+#[derive(Debug, BinRead, BinWrite)]
+#[br(import(ctx: &ReadContext))]
+#[bw(import(double_precision: bool))]
 pub struct PersistentAndRuntimeSaveData {
-    pub toc_blob: Array<u8, i64>,
-    pub data_blob: Array<u8, i64>,
+    pub toc_blob: TocBlob,
+    #[br(args(&toc_blob.headers, ctx))]
+    #[bw(args(double_precision))]
+    pub data_blob: DataBlob,
     pub level_to_destroyed_actors: Map<String, Array<ObjectReferenceDisc>>,
 }
@@ -0,0 +1,134 @@
+use binrw::binrw;
+
+use super::{guid::Guid, string::Name};
+
+/// The common header of every property in a property list.
+/// The terminating "None" property only consists of its name, so the name is read by the caller
+/// and passed in to decide whether a tag follows at all.
+#[binrw]
+#[derive(Debug, Clone)]
+#[br(import(name: Name))]
+pub struct PropertyTag {
+    #[br(calc = name)]
+    pub name: Name,
+    pub prop_type: Name,
+    pub size: i32,
+    pub array_index: i32,
+    #[br(args(&prop_type))]
+    pub data: PropertyTagData,
+    #[br(temp)]
+    #[bw(calc = property_guid.is_some() as u8)]
+    has_property_guid: u8,
+    #[br(if(has_property_guid != 0))]
+    pub property_guid: Option<Guid>,
+}
+
+/// The part of a [`PropertyTag`] that depends on the property type.
+#[binrw]
+#[derive(Debug, Clone)]
+#[br(import(prop_type: &Name))]
+pub enum PropertyTagData {
+    #[br(pre_assert(*prop_type == "StructProperty"))]
+    Struct {
+        struct_name: Name,
+        struct_guid: Guid,
+    },
+    #[br(pre_assert(*prop_type == "BoolProperty"))]
+    Bool {
+        #[br(map = |value: u8| value != 0)]
+        #[bw(map = |value: &bool| *value as u8)]
+        value: bool,
+    },
+    #[br(pre_assert(*prop_type == "ByteProperty"))]
+    Byte { enum_name: Name },
+    #[br(pre_assert(*prop_type == "EnumProperty"))]
+    Enum { enum_name: Name },
+    #[br(pre_assert(*prop_type == "ArrayProperty"))]
+    Array { inner_type: Name },
+    #[br(pre_assert(*prop_type == "SetProperty"))]
+    Set { inner_type: Name },
+    #[br(pre_assert(*prop_type == "MapProperty"))]
+    Map { key_type: Name, value_type: Name },
+    /// All other property types have no type-specific tag data.
+    #[br(pre_assert(!TYPES_WITH_TAG_DATA.iter().any(|known| prop_type == known)))]
+    None,
+}
+
+/// The property types whose tags carry type-specific data, which must not fall back to
+/// [`PropertyTagData::None`] when their data fails to read.
+const TYPES_WITH_TAG_DATA: &[&str] = &[
+    "StructProperty",
+    "BoolProperty",
+    "ByteProperty",
+    "EnumProperty",
+    "ArrayProperty",
+    "SetProperty",
+    "MapProperty",
+];
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::BinReaderExt;
+
+    use super::*;
+    use crate::{types::string::StringError, Error};
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as i32 + 1).to_le_bytes().to_vec();
+        data.extend(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    /// Reads a tag of the given type whose type-specific data is `tag_data`.
+    fn read(prop_type: &str, tag_data: &[u8]) -> Result<PropertyTag, Error> {
+        let mut data = string(prop_type);
+        data.extend(0i32.to_le_bytes());
+        data.extend(0i32.to_le_bytes());
+        data.extend(tag_data);
+        Cursor::new(data)
+            .read_le_args::<PropertyTag>((Name::from("mValue"),))
+            .map_err(Error::from)
+    }
+
+    #[test]
+    fn tag_without_data() {
+        let tag = read(
+            "IntProperty",
+            &[
+                1, 0xab, 0xab, 0xab, 0xab, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            ],
+        )
+        .unwrap();
+        assert!(matches!(tag.data, PropertyTagData::None));
+        assert_eq!(tag.property_guid.map(|guid| guid.a), Some(0xabababab));
+    }
+
+    #[test]
+    fn bad_byte_tag_reports_string_error() {
+        // an enum name that is missing its terminator.
+        let mut tag_data = 3i32.to_le_bytes().to_vec();
+        tag_data.extend(b"abc");
+        tag_data.push(0);
+        assert!(matches!(
+            read("ByteProperty", &tag_data),
+            Err(Error::InvalidString {
+                offset: 25,
+                reason: StringError::MissingTerminator
+            })
+        ));
+    }
+
+    #[test]
+    fn truncated_struct_tag_reports_end_of_file() {
+        let mut tag_data = string("Vector");
+        tag_data.extend([0; 8]);
+        let error = read("StructProperty", &tag_data).unwrap_err();
+        assert!(
+            matches!(&error, Error::BinRead(binrw::Error::Io(error)) if error.kind() == std::io::ErrorKind::UnexpectedEof),
+            "unexpected error: {error:?}"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use binrw::{BinRead, BinWrite};
+
+use crate::{parse_real, write_real};
+
+use super::{rotator::Rotator, vector3d::Vector3d};
+
+/// A rotation quaternion, stored as floats before Unreal Engine 5 and as doubles since.
+#[derive(Debug, Clone, Copy, PartialEq, BinRead, BinWrite)]
+#[br(import(double_precision: bool))]
+#[bw(import(double_precision: bool))]
+pub struct Quatd {
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub x: f64,
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub y: f64,
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub z: f64,
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub w: f64,
+}
+
+impl Default for Quatd {
+    fn default() -> Self {
+        Quatd::IDENTITY
+    }
+}
+
+impl Quatd {
+    pub const IDENTITY: Quatd = Quatd {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        w: 1.0,
+    };
+
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Quatd { x, y, z, w }
+    }
+
+    pub fn length(self) -> f64 {
+        (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt()
+    }
+
+    /// Returns the quaternion scaled to unit length, or the identity if it has no length.
+    pub fn normalized(self) -> Quatd {
+        let length = self.length();
+        if length == 0.0 {
+            Quatd::IDENTITY
+        } else {
+            Quatd::new(
+                self.x / length,
+                self.y / length,
+                self.z / length,
+                self.w / length,
+            )
+        }
+    }
+
+    /// Returns the row-major 3x3 rotation matrix `m` such that `m * v` rotates `v`.
+    pub fn to_rotation_matrix(self) -> [[f64; 3]; 3] {
+        let Quatd { x, y, z, w } = self.normalized();
+
+        [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ]
+    }
+
+    pub fn rotate_vector(self, v: Vector3d) -> Vector3d {
+        let [r0, r1, r2] = self.to_rotation_matrix();
+        Vector3d::new(
+            r0[0] * v.x + r0[1] * v.y + r0[2] * v.z,
+            r1[0] * v.x + r1[1] * v.y + r1[2] * v.z,
+            r2[0] * v.x + r2[1] * v.y + r2[2] * v.z,
+        )
+    }
+
+    /// Converts the rotation to Euler angles, following `FQuat::Rotator`.
+    pub fn to_rotator(self) -> Rotator {
+        const SINGULARITY_THRESHOLD: f64 = 0.4999995;
+
+        let Quatd { x, y, z, w } = self;
+        let singularity_test = z * x - w * y;
+        let yaw = (2.0 * (w * z + x * y))
+            .atan2(1.0 - 2.0 * (y * y + z * z))
+            .to_degrees();
+
+        if singularity_test < -SINGULARITY_THRESHOLD {
+            let roll = -yaw - 2.0 * x.atan2(w).to_degrees();
+            Rotator::new(-90.0, yaw, normalize_axis(roll))
+        } else if singularity_test > SINGULARITY_THRESHOLD {
+            let roll = yaw - 2.0 * x.atan2(w).to_degrees();
+            Rotator::new(90.0, yaw, normalize_axis(roll))
+        } else {
+            let pitch = (2.0 * singularity_test).asin().to_degrees();
+            let roll = (-2.0 * (w * x + y * z))
+                .atan2(1.0 - 2.0 * (x * x + y * y))
+                .to_degrees();
+            Rotator::new(pitch, yaw, roll)
+        }
+    }
+}
+
+/// Wraps an angle in degrees into the range (-180, 180].
+fn normalize_axis(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(360.0);
+    if angle > 180.0 {
+        angle - 360.0
+    } else {
+        angle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_rotator_close(actual: Rotator, expected: Rotator) {
+        let close = |a: f64, b: f64| normalize_axis(a - b).abs() < 1e-6;
+        assert!(
+            close(actual.pitch, expected.pitch)
+                && close(actual.yaw, expected.yaw)
+                && close(actual.roll, expected.roll),
+            "expected {expected:?} but found {actual:?}"
+        );
+    }
+
+    /// Whether two quaternions describe the same rotation, which `q` and `-q` both do.
+    fn assert_same_rotation(actual: Quatd, expected: Quatd) {
+        let dot = actual.x * expected.x
+            + actual.y * expected.y
+            + actual.z * expected.z
+            + actual.w * expected.w;
+        assert!(
+            (dot.abs() - 1.0).abs() < 1e-9,
+            "expected {expected:?} but found {actual:?}"
+        );
+    }
+
+    #[test]
+    fn identity() {
+        assert_eq!(Quatd::IDENTITY.to_rotator(), Rotator::default());
+        assert_eq!(
+            Quatd::IDENTITY.to_rotation_matrix(),
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        );
+        assert_eq!(Quatd::new(0.0, 0.0, 0.0, 0.0).normalized(), Quatd::IDENTITY);
+    }
+
+    #[test]
+    fn rotator_round_trip() {
+        for rotator in [
+            Rotator::new(0.0, 90.0, 0.0),
+            Rotator::new(30.0, -60.0, 45.0),
+            Rotator::new(-45.0, 179.0, -120.0),
+            Rotator::new(89.0, 10.0, 170.0),
+            Rotator::new(-89.0, -170.0, 5.0),
+        ] {
+            assert_rotator_close(rotator.to_quat().to_rotator(), rotator);
+        }
+    }
+
+    #[test]
+    fn gimbal_lock() {
+        for pitch in [90.0, -90.0] {
+            let rotator = Rotator::new(pitch, 30.0, 20.0);
+            let quat = rotator.to_quat();
+            let converted = quat.to_rotator();
+
+            // yaw and roll can't be told apart at ±90° pitch, but the rotation must be the same.
+            assert_eq!(converted.pitch, pitch);
+            assert_same_rotation(converted.to_quat(), quat);
+        }
+    }
+
+    #[test]
+    fn rotation_matrix_matches_rotate_vector() {
+        let quat = Rotator::new(30.0, -60.0, 45.0).to_quat();
+        let matrix = quat.to_rotation_matrix();
+        let x = quat.rotate_vector(Vector3d::new(1.0, 0.0, 0.0));
+        assert_eq!([x.x, x.y, x.z], [matrix[0][0], matrix[1][0], matrix[2][0]]);
+
+        // rotation matrices are orthonormal.
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, other) in matrix.iter().enumerate() {
+                let dot: f64 = row.iter().zip(other).map(|(a, b)| a * b).sum();
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((dot - expected).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn normalized_quaternions_rotate_the_same() {
+        let quat = Rotator::new(10.0, 20.0, 30.0).to_quat();
+        let scaled = Quatd::new(quat.x * 3.0, quat.y * 3.0, quat.z * 3.0, quat.w * 3.0);
+        let matrix = quat.to_rotation_matrix();
+        let scaled_matrix = scaled.to_rotation_matrix();
+        for (row, scaled_row) in matrix.iter().zip(&scaled_matrix) {
+            for (value, scaled_value) in row.iter().zip(scaled_row) {
+                assert!((value - scaled_value).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use binrw::{BinRead, BinWrite};
+
+use crate::{parse_real, write_real};
+
+use super::quatd::Quatd;
+
+/// Euler angles in degrees, stored as floats before Unreal Engine 5 and as doubles since.
+/// Pitch rotates around the Y axis, yaw around the Z axis and roll around the X axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, BinRead, BinWrite)]
+#[br(import(double_precision: bool))]
+#[bw(import(double_precision: bool))]
+pub struct Rotator {
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub pitch: f64,
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub yaw: f64,
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub roll: f64,
+}
+
+impl Rotator {
+    pub fn new(pitch: f64, yaw: f64, roll: f64) -> Self {
+        Rotator { pitch, yaw, roll }
+    }
+
+    /// Converts the rotation to a quaternion, following `FRotator::Quaternion`.
+    pub fn to_quat(self) -> Quatd {
+        let (sp, cp) = (self.pitch.to_radians() / 2.0).sin_cos();
+        let (sy, cy) = (self.yaw.to_radians() / 2.0).sin_cos();
+        let (sr, cr) = (self.roll.to_radians() / 2.0).sin_cos();
+
+        Quatd::new(
+            cr * sp * sy - sr * cp * cy,
+            -cr * sp * cy - sr * cp * sy,
+            cr * cp * sy - sr * sp * cy,
+            cr * cp * cy + sr * sp * sy,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::vector3d::Vector3d;
+
+    fn assert_close(actual: Vector3d, expected: Vector3d) {
+        assert!(
+            actual.distance(expected) < 1e-9,
+            "expected {expected:?} but found {actual:?}"
+        );
+    }
+
+    #[test]
+    fn identity() {
+        assert_eq!(Rotator::default().to_quat(), Quatd::IDENTITY);
+    }
+
+    #[test]
+    fn single_axis_rotations() {
+        let x = Vector3d::new(1.0, 0.0, 0.0);
+        let y = Vector3d::new(0.0, 1.0, 0.0);
+        let z = Vector3d::new(0.0, 0.0, 1.0);
+
+        // yaw turns X towards Y, pitch turns X up towards Z and roll turns Y down
+        // towards -Z.
+        assert_close(Rotator::new(0.0, 90.0, 0.0).to_quat().rotate_vector(x), y);
+        assert_close(Rotator::new(90.0, 0.0, 0.0).to_quat().rotate_vector(x), z);
+        assert_close(
+            Rotator::new(0.0, 0.0, 90.0).to_quat().rotate_vector(y),
+            Vector3d::new(0.0, 0.0, -1.0),
+        );
+    }
+
+    #[test]
+    fn quaternions_are_unit_length() {
+        for rotator in [
+            Rotator::new(10.0, 20.0, 30.0),
+            Rotator::new(-89.0, 179.0, -179.0),
+            Rotator::new(90.0, -45.0, 720.0),
+        ] {
+            assert!((rotator.to_quat().length() - 1.0).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use binrw::{BinRead, BinWrite};
+
+use super::{
+    string::{Name, String},
+    top_level_asset_path::TopLevelAssetPath,
+};
+use crate::save_version;
+
+#[derive(Debug, BinRead, BinWrite)]
+#[br(import(save_version: i32))]
+pub struct SoftObjectPath {
+    #[br(args(save_version))]
+    pub asset_path: SoftObjectAssetPath,
+    pub sub_path_string: String,
+}
+
+/// The asset a soft object path points to.
+/// Unreal Engine 5 splits it into package and asset name; older saves store a single name.
+#[derive(Debug, BinRead, BinWrite)]
+#[br(import(save_version: i32))]
+pub enum SoftObjectAssetPath {
+    #[br(pre_assert(save_version < save_version::UNREAL_ENGINE_5))]
+    Name(Name),
+    #[br(pre_assert(save_version >= save_version::UNREAL_ENGINE_5))]
+    TopLevel(TopLevelAssetPath),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::{BinReaderExt, BinWrite};
+
+    use super::*;
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as i32 + 1).to_le_bytes().to_vec();
+        data.extend(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    fn round_trip(save_version: i32, data: Vec<u8>) -> SoftObjectPath {
+        let path: SoftObjectPath = Cursor::new(&data).read_le_args((save_version,)).unwrap();
+        let mut written = Cursor::new(Vec::new());
+        path.write_le(&mut written).unwrap();
+        assert_eq!(written.into_inner(), data);
+        path
+    }
+
+    #[test]
+    fn pre_ue5_layout() {
+        let mut data =
+            string("/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate.Desc_IronPlate_C");
+        data.extend(string("SubObject"));
+
+        let path = round_trip(save_version::UNREAL_ENGINE_5 - 1, data);
+        let SoftObjectAssetPath::Name(name) = &path.asset_path else {
+            panic!("expected a single asset path name");
+        };
+        assert_eq!(
+            *name,
+            "/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate.Desc_IronPlate_C"
+        );
+        assert_eq!(path.sub_path_string, "SubObject");
+    }
+
+    #[test]
+    fn ue5_layout() {
+        let mut data = string("/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate");
+        data.extend(string("Desc_IronPlate_C"));
+        data.extend(string(""));
+
+        let path = round_trip(save_version::UNREAL_ENGINE_5, data);
+        let SoftObjectAssetPath::TopLevel(asset_path) = &path.asset_path else {
+            panic!("expected a top level asset path");
+        };
+        assert_eq!(
+            asset_path.package_name,
+            "/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate"
+        );
+        assert_eq!(asset_path.asset_name, "Desc_IronPlate_C");
+        assert_eq!(path.sub_path_string, "");
+    }
+}
@@ -1,9 +1,12 @@
 use std::{
+    cell::Cell,
     fmt,
-    io::{Read, Seek},
+    io::{Read, Seek, Write},
 };
 
-use binrw::{BinRead, BinResult, Endian, NullString, NullWideString};
+use binrw::{BinRead, BinResult, BinWrite, Endian, VecArgs};
+
+use crate::limits;
 
 /// A variable-length byte sequence of UTF-encoded characters, null-terminated:
 /// 4 byte signed integer length, encoded characters, null terminator.
@@ -19,6 +22,60 @@ pub enum String {
 
 pub type Name = String;
 
+/// How strictly the characters of a [`String`] are checked while reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StringDecoding {
+    /// Strings are kept even if they aren't valid UTF-8 or UTF-16, and invalid characters are
+    /// replaced when they are displayed.
+    #[default]
+    Lossy,
+    /// Invalid UTF-8 or UTF-16 is an error.
+    Strict,
+}
+
+thread_local! {
+    static DECODING: Cell<StringDecoding> = const { Cell::new(StringDecoding::Lossy) };
+}
+
+/// Reads every [`String`] inside `read` with the given decoding.
+///
+/// Strings are read all over the save format without access to the read arguments of their
+/// parents, so the decoding is set for the current thread instead of being passed down.
+pub fn with_string_decoding<T>(decoding: StringDecoding, read: impl FnOnce() -> T) -> T {
+    struct Restore(StringDecoding);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DECODING.set(self.0);
+        }
+    }
+
+    let _restore = Restore(DECODING.replace(decoding));
+    read()
+}
+
+/// Why a [`String`] could not be read.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StringError {
+    #[error("string length {0} is out of range")]
+    InvalidLength(i32),
+    #[error("string is missing its null terminator")]
+    MissingTerminator,
+    #[error("string of length {declared} ends after {actual} characters")]
+    LengthMismatch { declared: usize, actual: usize },
+    #[error("string is not valid UTF-8")]
+    InvalidUtf8,
+    #[error("string is not valid UTF-16")]
+    InvalidUtf16,
+}
+
+fn string_error(pos: u64, error: StringError) -> binrw::Error {
+    binrw::Error::Custom {
+        pos,
+        err: Box::new(error),
+    }
+}
+
 impl BinRead for String {
     type Args<'a> = ();
 
@@ -27,36 +84,87 @@ impl BinRead for String {
         endian: Endian,
         (): Self::Args<'_>,
     ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
         let length = <i32>::read_options(reader, endian, ())?;
+        let strict = DECODING.get() == StringDecoding::Strict;
+
         Ok(match length.cmp(&0) {
             std::cmp::Ordering::Less => {
-                let length = length * -2;
-                let string = <NullWideString>::read_options(reader, endian, ())?;
-                assert_eq!(
-                    string.0.len() + 2,
-                    length as usize,
-                    "expected utf-16 string of length {} but got {}",
-                    length,
-                    string.0.len()
-                );
-                String::UTF16(string.0)
+                let count = length
+                    .checked_neg()
+                    .ok_or_else(|| string_error(pos, StringError::InvalidLength(length)))?;
+                let count = limits::check_array_length(pos, "string length", count.into())?;
+                let mut units =
+                    Vec::<u16>::read_options(reader, endian, VecArgs { count, inner: () })?;
+                if units.pop() != Some(0) {
+                    return Err(string_error(pos, StringError::MissingTerminator));
+                }
+                check_terminator_position(pos, &units, 0)?;
+                if strict {
+                    char::decode_utf16(units.iter().copied())
+                        .try_for_each(|c| c.map(|_| ()))
+                        .map_err(|_| string_error(pos, StringError::InvalidUtf16))?;
+                }
+                String::UTF16(units)
             }
             std::cmp::Ordering::Equal => String::Empty,
             std::cmp::Ordering::Greater => {
-                let string = <NullString>::read_options(reader, endian, ())?;
-                assert_eq!(
-                    string.0.len() + 1,
-                    length as usize,
-                    "expected utf-8 string of length {} but got {}",
-                    length,
-                    string.0.len()
-                );
-                String::UTF8(string.0)
+                let count = limits::check_array_length(pos, "string length", length.into())?;
+                let mut bytes =
+                    Vec::<u8>::read_options(reader, endian, VecArgs { count, inner: () })?;
+                if bytes.pop() != Some(0) {
+                    return Err(string_error(pos, StringError::MissingTerminator));
+                }
+                check_terminator_position(pos, &bytes, 0)?;
+                if strict {
+                    std::str::from_utf8(&bytes)
+                        .map_err(|_| string_error(pos, StringError::InvalidUtf8))?;
+                }
+                String::UTF8(bytes)
             }
         })
     }
 }
 
+/// Returns an error if the string ends with a null character before its declared length.
+fn check_terminator_position<T: PartialEq>(pos: u64, characters: &[T], null: T) -> BinResult<()> {
+    match characters.iter().position(|c| *c == null) {
+        Some(actual) => Err(string_error(
+            pos,
+            StringError::LengthMismatch {
+                declared: characters.len() + 1,
+                actual,
+            },
+        )),
+        None => Ok(()),
+    }
+}
+
+impl BinWrite for String {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        match self {
+            String::Empty => 0i32.write_options(writer, endian, ()),
+            String::UTF8(v) => {
+                (v.len() as i32 + 1).write_options(writer, endian, ())?;
+                v.write_options(writer, endian, ())?;
+                0u8.write_options(writer, endian, ())
+            }
+            String::UTF16(v) => {
+                (-(v.len() as i32 + 1)).write_options(writer, endian, ())?;
+                v.write_options(writer, endian, ())?;
+                0u16.write_options(writer, endian, ())
+            }
+        }
+    }
+}
+
 fn utf8_string(bytes: &[u8]) -> std::string::String {
     std::string::String::from_utf8_lossy(bytes).to_string()
 }
@@ -65,6 +173,20 @@ fn utf16_string(bytes: &[u16]) -> std::string::String {
     std::string::String::from_utf16_lossy(bytes).to_string()
 }
 
+/// Strings are stored the way Unreal writes them: UTF-8 if every character is ASCII, UTF-16
+/// otherwise.
+impl From<&str> for String {
+    fn from(value: &str) -> Self {
+        if value.is_empty() {
+            String::Empty
+        } else if value.is_ascii() {
+            String::UTF8(value.as_bytes().to_vec())
+        } else {
+            String::UTF16(value.encode_utf16().collect())
+        }
+    }
+}
+
 impl PartialEq<&str> for String {
     fn eq(&self, other: &&str) -> bool {
         match self {
@@ -94,3 +216,121 @@ impl fmt::Debug for String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::BinReaderExt;
+
+    use super::*;
+    use crate::Error;
+
+    fn read(data: &[u8], decoding: StringDecoding) -> Result<String, Error> {
+        with_string_decoding(decoding, || Cursor::new(data).read_le::<String>())
+            .map_err(Error::from)
+    }
+
+    fn utf8(characters: &[u8]) -> Vec<u8> {
+        let mut data = (characters.len() as i32).to_le_bytes().to_vec();
+        data.extend(characters);
+        data
+    }
+
+    fn utf16(units: &[u16]) -> Vec<u8> {
+        let mut data = (-(units.len() as i32)).to_le_bytes().to_vec();
+        data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+        data
+    }
+
+    fn reason(result: Result<String, Error>) -> StringError {
+        match result {
+            Err(Error::InvalidString { reason, .. }) => reason,
+            result => panic!("expected an invalid string, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn read_utf8_and_utf16() {
+        let string = read(&utf8(b"abc\0"), StringDecoding::Strict).unwrap();
+        assert_eq!(string, String::UTF8(b"abc".to_vec()));
+        let string = read(&utf16(&[0xe9, 0]), StringDecoding::Strict).unwrap();
+        assert_eq!(string.to_string(), "é");
+        assert_eq!(
+            read(&utf8(b""), StringDecoding::Strict).unwrap(),
+            String::Empty
+        );
+    }
+
+    #[test]
+    fn missing_terminator() {
+        for decoding in [StringDecoding::Lossy, StringDecoding::Strict] {
+            assert_eq!(
+                reason(read(&utf8(b"abc"), decoding)),
+                StringError::MissingTerminator
+            );
+            assert_eq!(
+                reason(read(&utf16(&[0x61, 0x62]), decoding)),
+                StringError::MissingTerminator
+            );
+        }
+    }
+
+    #[test]
+    fn length_mismatch() {
+        for decoding in [StringDecoding::Lossy, StringDecoding::Strict] {
+            assert_eq!(
+                reason(read(&utf8(b"ab\0cd\0"), decoding)),
+                StringError::LengthMismatch {
+                    declared: 6,
+                    actual: 2
+                }
+            );
+            assert_eq!(
+                reason(read(&utf16(&[0x61, 0, 0]), decoding)),
+                StringError::LengthMismatch {
+                    declared: 3,
+                    actual: 1
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn minimum_length() {
+        let data = i32::MIN.to_le_bytes();
+        assert_eq!(
+            reason(read(&data, StringDecoding::Lossy)),
+            StringError::InvalidLength(i32::MIN)
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let data = 100i32.to_le_bytes();
+        assert!(read(&data, StringDecoding::Lossy).is_err());
+    }
+
+    #[test]
+    fn invalid_characters_in_strict_mode() {
+        assert_eq!(
+            reason(read(&utf8(b"\xff\0"), StringDecoding::Strict)),
+            StringError::InvalidUtf8
+        );
+        assert_eq!(
+            reason(read(&utf16(&[0xd800, 0]), StringDecoding::Strict)),
+            StringError::InvalidUtf16
+        );
+    }
+
+    #[test]
+    fn invalid_characters_in_lossy_mode() {
+        let string = read(&utf8(b"a\xff\0"), StringDecoding::Lossy).unwrap();
+        assert_eq!(string, String::UTF8(b"a\xff".to_vec()));
+        assert_eq!(string.to_string(), "a\u{fffd}");
+
+        let string = read(&utf16(&[0xd800, 0]), StringDecoding::Lossy).unwrap();
+        assert_eq!(string, String::UTF16(vec![0xd800]));
+        assert_eq!(string.to_string(), "\u{fffd}");
+    }
+}
@@ -1,27 +1,661 @@
-use binrw::BinRead;
+use std::io::{Read, Seek, Write};
 
-use crate::adabool;
+use binrw::{BinRead, BinResult, BinWrite, Endian};
 
-use super::string::String;
+use crate::{adabool, date_time::civil_from_ticks, limits, to_adabool};
 
-#[derive(BinRead)]
+use super::{
+    array::Array, format_argument_data::FormatArgumentData,
+    format_argument_value::FormatArgumentValue, map::Map,
+    number_formatting_options::NumberFormattingOptions, string::Name, string::String,
+};
+
+/// The history of a [`Text`] nested `depth` texts deep.
+#[derive(Debug, BinRead, BinWrite)]
+#[br(import(depth: usize))]
 pub enum TextHistoryType {
-    #[br(magic = -1i8)]
+    #[brw(magic = -1i8)]
     None {
         #[br(map = adabool)]
+        #[bw(map = to_adabool)]
         has_culture_invariant_string: bool,
         #[br(if(has_culture_invariant_string))]
+        #[bw(if(has_culture_invariant_string != 0))]
         text_data: String,
     },
-    #[br(magic = 0i8)]
+    #[brw(magic = 0i8)]
     Base {
         namespace: String,
         key: String,
         source_string: String,
     },
+    #[brw(magic = 1i8)]
+    NamedFormat {
+        #[br(args(depth + 1))]
+        source_format: Box<Text>,
+        #[br(args(depth))]
+        arguments: Map<String, FormatArgumentValue>,
+    },
+    #[brw(magic = 2i8)]
+    OrderedFormat {
+        #[br(args(depth + 1))]
+        source_format: Box<Text>,
+        #[br(args { inner: (depth,) })]
+        arguments: Array<FormatArgumentValue>,
+    },
+    #[brw(magic = 3i8)]
+    ArgumentFormat {
+        #[br(args(depth + 1))]
+        source_format: Box<Text>,
+        #[br(args { inner: (depth,) })]
+        arguments: Array<FormatArgumentData>,
+    },
+    #[brw(magic = 4i8)]
+    AsNumber {
+        #[br(args(depth))]
+        source_value: FormatArgumentValue,
+        #[br(map = adabool)]
+        #[bw(map = to_adabool)]
+        has_format_options: bool,
+        #[br(if(has_format_options))]
+        format_options: Option<NumberFormattingOptions>,
+        target_culture: String,
+    },
+    #[brw(magic = 5i8)]
+    AsPercent {
+        #[br(args(depth))]
+        source_value: FormatArgumentValue,
+        #[br(map = adabool)]
+        #[bw(map = to_adabool)]
+        has_format_options: bool,
+        #[br(if(has_format_options))]
+        format_options: Option<NumberFormattingOptions>,
+        target_culture: String,
+    },
+    #[brw(magic = 6i8)]
+    AsCurrency {
+        currency_code: String,
+        #[br(args(depth))]
+        source_value: FormatArgumentValue,
+        #[br(map = adabool)]
+        #[bw(map = to_adabool)]
+        has_format_options: bool,
+        #[br(if(has_format_options))]
+        format_options: Option<NumberFormattingOptions>,
+        target_culture: String,
+    },
+    #[brw(magic = 7i8)]
+    AsDate {
+        /// Ticks of 100 nanoseconds since 0001-01-01.
+        source_date_time: i64,
+        date_style: i8,
+        time_zone: String,
+        target_culture: String,
+    },
+    #[brw(magic = 8i8)]
+    AsTime {
+        /// Ticks of 100 nanoseconds since 0001-01-01.
+        source_date_time: i64,
+        time_style: i8,
+        time_zone: String,
+        target_culture: String,
+    },
+    #[brw(magic = 9i8)]
+    AsDateTime {
+        /// Ticks of 100 nanoseconds since 0001-01-01.
+        source_date_time: i64,
+        date_style: i8,
+        time_style: i8,
+        time_zone: String,
+        target_culture: String,
+    },
+    #[brw(magic = 10i8)]
+    Transform {
+        #[br(args(depth + 1))]
+        source_text: Box<Text>,
+        transform_type: TextTransformType,
+    },
+    #[brw(magic = 11i8)]
+    StringTableEntry { table_id: Name, key: String },
+    #[brw(magic = 12i8)]
+    TextGenerator {
+        generator_type_id: Name,
+        #[br(if(generator_type_id != "None"))]
+        generator_contents: Option<Array<u8>>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BinRead, BinWrite)]
+#[brw(repr = u8)]
+pub enum TextTransformType {
+    ToLower = 0,
+    ToUpper = 1,
 }
 
+/// Texts can contain other texts, as format patterns, format arguments and transformed text.
+/// They are read with the number of texts they are nested in, which is limited by
+/// [`ReadLimits::max_nesting_depth`](crate::ReadLimits::max_nesting_depth).
+#[derive(Debug)]
 pub struct Text {
     pub flags: u32,
     pub history_type: TextHistoryType,
 }
+
+impl BinRead for Text {
+    type Args<'a> = (usize,);
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        (depth,): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        limits::check_nesting_depth(reader.stream_position()?, "text nesting depth", depth)?;
+        Ok(Text {
+            flags: u32::read_options(reader, endian, ())?,
+            history_type: TextHistoryType::read_options(reader, endian, (depth,))?,
+        })
+    }
+}
+
+impl BinWrite for Text {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.flags.write_options(writer, endian, ())?;
+        self.history_type.write_options(writer, endian, ())
+    }
+}
+
+impl Text {
+    /// Renders the text the way the game would display it.
+    ///
+    /// Format patterns have their `{argument}` placeholders substituted, numbers are formatted
+    /// with their formatting options and dates and times are rendered in ISO 8601 order.
+    /// Culture-specific formatting is not applied, and string table entries render as their key
+    /// because the tables themselves are not part of the save.
+    pub fn to_display_string(&self) -> std::string::String {
+        match &self.history_type {
+            TextHistoryType::None { text_data, .. } => text_data.to_string(),
+            TextHistoryType::Base { source_string, .. } => source_string.to_string(),
+            TextHistoryType::NamedFormat {
+                source_format,
+                arguments,
+            } => format_pattern(&source_format.to_display_string(), |name| {
+                arguments
+                    .0
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_display_string())
+            }),
+            TextHistoryType::OrderedFormat {
+                source_format,
+                arguments,
+            } => format_pattern(&source_format.to_display_string(), |name| {
+                let index = name.parse::<usize>().ok()?;
+                arguments
+                    .0
+                    .get(index)
+                    .map(|value| value.to_display_string())
+            }),
+            TextHistoryType::ArgumentFormat {
+                source_format,
+                arguments,
+            } => format_pattern(&source_format.to_display_string(), |name| {
+                arguments
+                    .0
+                    .iter()
+                    .find(|argument| argument.argument_name == name)
+                    .map(|argument| argument.value.to_display_string())
+            }),
+            TextHistoryType::AsNumber {
+                source_value,
+                format_options,
+                ..
+            } => format_number(
+                source_value_as_f64(source_value),
+                &NumberFormat::from_options(format_options.as_ref()),
+            ),
+            TextHistoryType::AsPercent {
+                source_value,
+                format_options,
+                ..
+            } => {
+                let format = match format_options {
+                    Some(options) => NumberFormat::from_options(Some(options)),
+                    None => NumberFormat {
+                        maximum_fractional_digits: 0,
+                        ..NumberFormat::default()
+                    },
+                };
+                format!(
+                    "{}%",
+                    format_number(source_value_as_f64(source_value) * 100.0, &format)
+                )
+            }
+            TextHistoryType::AsCurrency {
+                currency_code,
+                source_value,
+                format_options,
+                ..
+            } => {
+                let format = match format_options {
+                    Some(options) => NumberFormat::from_options(Some(options)),
+                    None => NumberFormat {
+                        minimum_fractional_digits: 2,
+                        maximum_fractional_digits: 2,
+                        ..NumberFormat::default()
+                    },
+                };
+                format!(
+                    "{currency_code} {}",
+                    format_number(source_value_as_f64(source_value), &format)
+                )
+            }
+            TextHistoryType::AsDate {
+                source_date_time, ..
+            } => {
+                let (year, month, day, ..) = civil_from_ticks(*source_date_time);
+                format!("{year:04}-{month:02}-{day:02}")
+            }
+            TextHistoryType::AsTime {
+                source_date_time, ..
+            } => {
+                let (.., hour, minute, second) = civil_from_ticks(*source_date_time);
+                format!("{hour:02}:{minute:02}:{second:02}")
+            }
+            TextHistoryType::AsDateTime {
+                source_date_time, ..
+            } => {
+                let (year, month, day, hour, minute, second) = civil_from_ticks(*source_date_time);
+                format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}")
+            }
+            TextHistoryType::Transform {
+                source_text,
+                transform_type,
+            } => {
+                let text = source_text.to_display_string();
+                match transform_type {
+                    TextTransformType::ToLower => text.to_lowercase(),
+                    TextTransformType::ToUpper => text.to_uppercase(),
+                }
+            }
+            TextHistoryType::StringTableEntry { key, .. } => key.to_string(),
+            TextHistoryType::TextGenerator { .. } => std::string::String::new(),
+        }
+    }
+}
+
+fn source_value_as_f64(value: &FormatArgumentValue) -> f64 {
+    match value {
+        FormatArgumentValue::Int(value) => *value as f64,
+        FormatArgumentValue::UInt(value) => *value as f64,
+        FormatArgumentValue::Float(value) => f64::from(*value),
+        FormatArgumentValue::Double(value) => *value,
+        FormatArgumentValue::Text(_) | FormatArgumentValue::Gender(_) => 0.0,
+    }
+}
+
+/// Substitutes `{argument}` placeholders in a format pattern.
+/// A backtick escapes the following brace or backtick; placeholders without a matching argument
+/// are kept as they are.
+fn format_pattern(
+    pattern: &str,
+    argument: impl Fn(&str) -> Option<std::string::String>,
+) -> std::string::String {
+    let mut result = std::string::String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '`' => match chars.clone().next() {
+                Some(next @ ('{' | '}' | '`')) => {
+                    result.push(next);
+                    chars.next();
+                }
+                _ => result.push(c),
+            },
+            '{' => {
+                let rest = chars.as_str();
+                match rest.find('}') {
+                    Some(end) => {
+                        let name = &rest[..end];
+                        match argument(name) {
+                            Some(value) => result.push_str(&value),
+                            None => {
+                                result.push('{');
+                                result.push_str(name);
+                                result.push('}');
+                            }
+                        }
+                        chars = rest[end + 1..].chars();
+                    }
+                    None => result.push(c),
+                }
+            }
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// The subset of [`NumberFormattingOptions`] used when rendering numbers.
+pub(crate) struct NumberFormat {
+    pub always_sign: bool,
+    pub use_grouping: bool,
+    pub minimum_integral_digits: usize,
+    pub minimum_fractional_digits: usize,
+    pub maximum_fractional_digits: usize,
+}
+
+impl Default for NumberFormat {
+    /// Unreal's default formatting options with grouping, as used for format arguments.
+    fn default() -> Self {
+        NumberFormat {
+            always_sign: false,
+            use_grouping: true,
+            minimum_integral_digits: 1,
+            minimum_fractional_digits: 0,
+            maximum_fractional_digits: 3,
+        }
+    }
+}
+
+impl NumberFormat {
+    fn from_options(options: Option<&NumberFormattingOptions>) -> Self {
+        match options {
+            Some(options) => NumberFormat {
+                always_sign: options.always_sign,
+                use_grouping: options.use_grouping,
+                minimum_integral_digits: clamp_digits(options.minimum_integral_digits),
+                minimum_fractional_digits: clamp_digits(options.minimum_fractional_digits),
+                maximum_fractional_digits: clamp_digits(options.maximum_fractional_digits),
+            },
+            None => NumberFormat::default(),
+        }
+    }
+}
+
+/// The most digits an `f64` can need on either side of the decimal point.
+/// Digit counts come straight from the save, so they are limited to keep formatting cheap.
+const MAX_DIGITS: i32 = 340;
+
+fn clamp_digits(digits: i32) -> usize {
+    digits.clamp(0, MAX_DIGITS) as usize
+}
+
+pub(crate) fn format_number(value: f64, format: &NumberFormat) -> std::string::String {
+    let maximum_fractional_digits = format
+        .maximum_fractional_digits
+        .max(format.minimum_fractional_digits);
+    let formatted = format!("{:.*}", maximum_fractional_digits, value.abs());
+    let (integral, fractional) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+    // drop trailing zeroes down to the minimum number of fractional digits.
+    let mut fractional = fractional.to_owned();
+    while fractional.len() > format.minimum_fractional_digits && fractional.ends_with('0') {
+        fractional.pop();
+    }
+
+    let integral = format!(
+        "{integral:0>width$}",
+        width = format.minimum_integral_digits
+    );
+    let integral = if format.use_grouping {
+        let digits = integral.as_bytes();
+        let mut grouped = std::string::String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, digit) in digits.iter().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(char::from(*digit));
+        }
+        grouped
+    } else {
+        integral
+    };
+
+    let is_zero =
+        integral.bytes().all(|b| b == b'0' || b == b',') && fractional.bytes().all(|b| b == b'0');
+    let sign = if value < 0.0 && !is_zero {
+        "-"
+    } else if format.always_sign {
+        "+"
+    } else {
+        ""
+    };
+
+    if fractional.is_empty() {
+        format!("{sign}{integral}")
+    } else {
+        format!("{sign}{integral}.{fractional}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::BinReaderExt;
+
+    use super::*;
+    use crate::{limits::ReadLimits, Error};
+
+    fn text(history_type: TextHistoryType) -> Text {
+        Text {
+            flags: 0,
+            history_type,
+        }
+    }
+
+    fn base(source_string: &str) -> Box<Text> {
+        Box::new(text(TextHistoryType::Base {
+            namespace: String::Empty,
+            key: String::Empty,
+            source_string: source_string.into(),
+        }))
+    }
+
+    fn options(
+        minimum_fractional_digits: i32,
+        maximum_fractional_digits: i32,
+    ) -> NumberFormattingOptions {
+        NumberFormattingOptions {
+            always_sign: false,
+            use_grouping: true,
+            rounding_mode: 0,
+            minimum_integral_digits: 1,
+            maximum_integral_digits: 324,
+            minimum_fractional_digits,
+            maximum_fractional_digits,
+        }
+    }
+
+    #[test]
+    fn format_pattern_substitutes_arguments() {
+        let argument = |name: &str| (name == "count").then(|| "3".to_owned());
+        assert_eq!(format_pattern("{count} items", argument), "3 items");
+        assert_eq!(format_pattern("{count}/{count}", argument), "3/3");
+        assert_eq!(
+            format_pattern("{missing} items", argument),
+            "{missing} items"
+        );
+        assert_eq!(format_pattern("`{count`} `` `x", argument), "{count} ` `x");
+        assert_eq!(
+            format_pattern("unclosed {count", argument),
+            "unclosed {count"
+        );
+    }
+
+    #[test]
+    fn named_format() {
+        let text = text(TextHistoryType::NamedFormat {
+            source_format: base("{Amount} x {Item}"),
+            arguments: Map([
+                ("Item".into(), FormatArgumentValue::Text(base("Iron Plate"))),
+                ("Amount".into(), FormatArgumentValue::Int(1200)),
+            ]
+            .into_iter()
+            .collect()),
+        });
+        assert_eq!(text.to_display_string(), "1,200 x Iron Plate");
+    }
+
+    #[test]
+    fn ordered_format() {
+        let text = text(TextHistoryType::OrderedFormat {
+            source_format: base("{1} of {0}, {2}"),
+            arguments: Array::from(vec![
+                FormatArgumentValue::UInt(10),
+                FormatArgumentValue::Double(2.5),
+            ]),
+        });
+        assert_eq!(text.to_display_string(), "2.5 of 10, {2}");
+    }
+
+    #[test]
+    fn argument_format() {
+        let text = text(TextHistoryType::ArgumentFormat {
+            source_format: base("{Name}: {Speed}"),
+            arguments: Array::from(vec![
+                FormatArgumentData {
+                    argument_name: "Speed".into(),
+                    value: FormatArgumentValue::Float(0.125),
+                },
+                FormatArgumentData {
+                    argument_name: "Name".into(),
+                    value: FormatArgumentValue::Text(base("Belt")),
+                },
+            ]),
+        });
+        assert_eq!(text.to_display_string(), "Belt: 0.125");
+    }
+
+    fn as_percent(value: f64, format_options: Option<NumberFormattingOptions>) -> Text {
+        text(TextHistoryType::AsPercent {
+            source_value: FormatArgumentValue::Double(value),
+            has_format_options: format_options.is_some(),
+            format_options,
+            target_culture: String::Empty,
+        })
+    }
+
+    fn as_currency(value: f64, format_options: Option<NumberFormattingOptions>) -> Text {
+        text(TextHistoryType::AsCurrency {
+            currency_code: "EUR".into(),
+            source_value: FormatArgumentValue::Double(value),
+            has_format_options: format_options.is_some(),
+            format_options,
+            target_culture: String::Empty,
+        })
+    }
+
+    #[test]
+    fn percent_defaults_only_without_options() {
+        assert_eq!(as_percent(0.1234, None).to_display_string(), "12%");
+        assert_eq!(
+            as_percent(0.1234, Some(options(1, 2))).to_display_string(),
+            "12.34%"
+        );
+    }
+
+    #[test]
+    fn currency_defaults_only_without_options() {
+        assert_eq!(
+            as_currency(1234.5, None).to_display_string(),
+            "EUR 1,234.50"
+        );
+        assert_eq!(
+            as_currency(1234.5, Some(options(0, 0))).to_display_string(),
+            "EUR 1,234"
+        );
+    }
+
+    #[test]
+    fn format_number_grouping() {
+        let format = NumberFormat::default();
+        assert_eq!(format_number(0.0, &format), "0");
+        assert_eq!(format_number(999.0, &format), "999");
+        assert_eq!(format_number(1000.0, &format), "1,000");
+        assert_eq!(format_number(1234567.891, &format), "1,234,567.891");
+        let format = NumberFormat {
+            use_grouping: false,
+            minimum_integral_digits: 5,
+            ..NumberFormat::default()
+        };
+        assert_eq!(format_number(1234.0, &format), "01234");
+    }
+
+    #[test]
+    fn format_number_sign() {
+        let format = NumberFormat::default();
+        assert_eq!(format_number(-1234.5, &format), "-1,234.5");
+        // values that round to zero have no sign.
+        assert_eq!(format_number(-0.0001, &format), "0");
+        let format = NumberFormat {
+            always_sign: true,
+            ..NumberFormat::default()
+        };
+        assert_eq!(format_number(12.0, &format), "+12");
+        assert_eq!(format_number(0.0, &format), "+0");
+        assert_eq!(format_number(-12.0, &format), "-12");
+    }
+
+    #[test]
+    fn huge_digit_counts_are_clamped() {
+        let options = NumberFormattingOptions {
+            always_sign: false,
+            use_grouping: false,
+            rounding_mode: 0,
+            minimum_integral_digits: i32::MAX,
+            maximum_integral_digits: i32::MAX,
+            minimum_fractional_digits: i32::MAX,
+            maximum_fractional_digits: i32::MAX,
+        };
+        let formatted = format_number(1.5, &NumberFormat::from_options(Some(&options)));
+        let padding = "0".repeat(MAX_DIGITS as usize - 1);
+        assert_eq!(formatted, format!("{padding}1.5{padding}"));
+    }
+
+    /// A text made of `depth` nested transforms around an empty culture invariant text.
+    fn nested_transforms(depth: usize) -> Vec<u8> {
+        let mut data = Vec::new();
+        for _ in 0..depth {
+            data.extend(0u32.to_le_bytes());
+            data.push(10);
+        }
+        data.extend(0u32.to_le_bytes());
+        data.push(-1i8 as u8);
+        data.extend(0u32.to_le_bytes());
+        data.extend(std::iter::repeat_n(0, depth));
+        data
+    }
+
+    #[test]
+    fn nested_text_within_limit() {
+        let data = nested_transforms(64);
+        let text = Cursor::new(data).read_le_args::<Text>((0,)).unwrap();
+        assert_eq!(text.to_display_string(), "");
+    }
+
+    #[test]
+    fn nested_text_over_limit() {
+        let limits = ReadLimits {
+            max_nesting_depth: 8,
+            ..ReadLimits::default()
+        };
+        let data = nested_transforms(100_000);
+        let error = limits::with_read_limits(limits, || {
+            Cursor::new(data).read_le_args::<Text>((0,)).unwrap_err()
+        });
+        assert!(matches!(
+            Error::from(error),
+            Error::LimitExceeded { reason, .. } if reason.requested == 9 && reason.max == 8
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+use std::io::{Read, Seek, Write};
+
+use binrw::{BinRead, BinResult, BinWrite, Endian};
+
+use crate::limits;
+
+use super::{
+    array::{write_length, write_size_prefixed, Array},
+    generic_object_save_header::GenericObjectSaveHeader,
+    object_reference_disc::ObjectReferenceDisc,
+};
+
+/// The decoded contents of a `TOCBlob64` buffer.
+/// The buffer is prefixed by its int64 size, which is used to detect the optional trailing list of
+/// destroyed actors.
+#[derive(Debug)]
+pub struct TocBlob {
+    pub headers: Vec<GenericObjectSaveHeader>,
+    pub destroyed_actors: Option<Array<ObjectReferenceDisc>>,
+}
+
+impl BinRead for TocBlob {
+    type Args<'a> = ();
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
+        let size = i64::read_options(reader, endian, ())?;
+        let end = reader.stream_position()? + limits::check_body_size(pos, "TOC blob size", size)?;
+
+        let headers =
+            Array::<GenericObjectSaveHeader>::read_options(reader, endian, Default::default())?.0;
+
+        // destroyed actors are only written if there are any, so we can only tell by checking if
+        // there is any data left in the blob.
+        let destroyed_actors = if reader.stream_position()? < end {
+            Some(Array::read_options(reader, endian, Default::default())?)
+        } else {
+            None
+        };
+
+        let pos = reader.stream_position()?;
+        if pos != end {
+            return Err(binrw::Error::AssertFail {
+                pos,
+                message: format!("expected TOC blob to end at {end} but it ended at {pos}"),
+            });
+        }
+
+        Ok(TocBlob {
+            headers,
+            destroyed_actors,
+        })
+    }
+}
+
+impl BinWrite for TocBlob {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_toc_blob(
+            writer,
+            endian,
+            self.headers.iter(),
+            self.destroyed_actors.as_ref().map(|actors| &actors.0[..]),
+        )
+    }
+}
+
+/// Writes a TOC blob prefixed by its size, with the destroyed actors only if there are any.
+pub(crate) fn write_toc_blob<'a, W: Write + Seek>(
+    writer: &mut W,
+    endian: Endian,
+    headers: impl ExactSizeIterator<Item = &'a GenericObjectSaveHeader>,
+    destroyed_actors: Option<&[ObjectReferenceDisc]>,
+) -> BinResult<()> {
+    write_size_prefixed::<i64, _>(writer, endian, |writer| {
+        write_length::<i32, _>(writer, headers.len())?.write_options(writer, endian, ())?;
+        for header in headers {
+            header.write_options(writer, endian, ())?;
+        }
+        if let Some(destroyed_actors) = destroyed_actors {
+            write_length::<i32, _>(writer, destroyed_actors.len())?.write_options(
+                writer,
+                endian,
+                (),
+            )?;
+            destroyed_actors.write_options(writer, endian, ())?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::BinReaderExt;
+
+    use super::*;
+    use crate::Error;
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as i32 + 1).to_le_bytes().to_vec();
+        data.extend(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    fn reference(path_name: &str) -> Vec<u8> {
+        let mut data = string("Persistent_Level");
+        data.extend(string(path_name));
+        data
+    }
+
+    fn actor_header(class_name: &str, path_name: &str, translation: [f32; 3]) -> Vec<u8> {
+        let mut data = 1u32.to_le_bytes().to_vec();
+        data.extend(string(class_name));
+        data.extend(reference(path_name));
+        data.extend(1u32.to_le_bytes());
+        for value in [0.0, 0.0, 0.0, 1.0]
+            .iter()
+            .chain(&translation)
+            .chain(&[1.0; 3])
+        {
+            data.extend(f32::to_le_bytes(*value));
+        }
+        data.extend(1u32.to_le_bytes());
+        data
+    }
+
+    fn object_header(class_name: &str, path_name: &str, outer_path_name: &str) -> Vec<u8> {
+        let mut data = 0u32.to_le_bytes().to_vec();
+        data.extend(string(class_name));
+        data.extend(reference(path_name));
+        data.extend(string(outer_path_name));
+        data
+    }
+
+    /// A TOC blob with a conveyor belt actor and its inventory component.
+    fn toc_blob(destroyed_actors: Option<&[&str]>) -> Vec<u8> {
+        let mut contents = 2i32.to_le_bytes().to_vec();
+        contents.extend(actor_header(
+            "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk1/Build_ConveyorBeltMk1.Build_ConveyorBeltMk1_C",
+            "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1",
+            [100.0, -200.0, 300.0],
+        ));
+        contents.extend(object_header(
+            "/Script/FactoryGame.FGInventoryComponent",
+            "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1.StorageInventory",
+            "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1",
+        ));
+        if let Some(destroyed_actors) = destroyed_actors {
+            contents.extend((destroyed_actors.len() as i32).to_le_bytes());
+            for path_name in destroyed_actors {
+                contents.extend(reference(path_name));
+            }
+        }
+        let mut data = (contents.len() as i64).to_le_bytes().to_vec();
+        data.extend(contents);
+        data
+    }
+
+    /// Reads the blob and checks that writing it gives back the same bytes.
+    fn round_trip(data: &[u8]) -> TocBlob {
+        let mut reader = Cursor::new(data);
+        let blob = reader.read_le::<TocBlob>().unwrap();
+        assert_eq!(reader.position(), data.len() as u64);
+        let mut written = Cursor::new(Vec::new());
+        blob.write_le(&mut written).unwrap();
+        assert_eq!(written.into_inner(), data);
+        blob
+    }
+
+    #[test]
+    fn headers_and_destroyed_actors() {
+        let blob = round_trip(&toc_blob(Some(&[
+            "Persistent_Level:PersistentLevel.BP_ResourceNode_C_12",
+            "Persistent_Level:PersistentLevel.Foliage_Rock_3",
+        ])));
+
+        assert_eq!(blob.headers.len(), 2);
+        let GenericObjectSaveHeader::Actor(actor) = &blob.headers[0] else {
+            panic!("expected an actor header but found {:?}", blob.headers[0]);
+        };
+        assert_eq!(
+            actor.object_header.reference.path_name,
+            "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1"
+        );
+        assert!(actor.need_transform);
+        assert_eq!(actor.transform.translation, [100.0, -200.0, 300.0]);
+        assert!(actor.was_placed_in_level);
+
+        let GenericObjectSaveHeader::Object(object) = &blob.headers[1] else {
+            panic!("expected an object header but found {:?}", blob.headers[1]);
+        };
+        assert_eq!(
+            object.base_header.class_name,
+            "/Script/FactoryGame.FGInventoryComponent"
+        );
+        assert_eq!(
+            object.outer_path_name,
+            "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1"
+        );
+        assert!(blob.headers[1].transform().is_none());
+
+        let destroyed_actors = blob.destroyed_actors.unwrap().0;
+        assert_eq!(destroyed_actors.len(), 2);
+        assert_eq!(destroyed_actors[0].level_name, "Persistent_Level");
+        assert_eq!(
+            destroyed_actors[1].path_name,
+            "Persistent_Level:PersistentLevel.Foliage_Rock_3"
+        );
+    }
+
+    #[test]
+    fn without_destroyed_actors() {
+        let blob = round_trip(&toc_blob(None));
+        assert_eq!(blob.headers.len(), 2);
+        assert!(blob.destroyed_actors.is_none());
+
+        // an empty list is still written, as the game did.
+        let blob = round_trip(&toc_blob(Some(&[])));
+        assert!(blob.destroyed_actors.unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn headers_overrunning_the_size() {
+        let mut data = toc_blob(None);
+        let size = i64::from_le_bytes(data[..8].try_into().unwrap());
+        data.splice(..8, (size - 2).to_le_bytes());
+        let error = Cursor::new(&data).read_le::<TocBlob>().unwrap_err();
+        assert!(matches!(
+            error,
+            binrw::Error::AssertFail { pos, .. } if pos == data.len() as u64
+        ));
+    }
+
+    #[test]
+    fn negative_size() {
+        let mut data = (-4i64).to_le_bytes().to_vec();
+        data.extend(0i32.to_le_bytes());
+        let error = Cursor::new(data).read_le::<TocBlob>().unwrap_err();
+        assert!(matches!(
+            Error::from(error),
+            Error::LimitExceeded { offset: 0, reason } if reason.requested == -4
+        ));
+    }
+}
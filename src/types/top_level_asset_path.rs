@@ -0,0 +1,9 @@
+use binrw::{BinRead, BinWrite};
+
+use super::string::Name;
+
+#[derive(Debug, BinRead, BinWrite)]
+pub struct TopLevelAssetPath {
+    pub package_name: Name,
+    pub asset_name: Name,
+}
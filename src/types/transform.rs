@@ -1,6 +1,6 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
-#[derive(BinRead)]
+#[derive(Debug, BinRead, BinWrite)]
 pub struct Transform {
     pub rotation: [f32; 4],
     pub translation: [f32; 3],
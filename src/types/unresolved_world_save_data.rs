@@ -1,8 +1,8 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
 use super::{array::Array, object_reference_disc::ObjectReferenceDisc};
 
-#[derive(BinRead)]
+#[derive(Debug, BinRead, BinWrite)]
 pub struct UnresolvedWorldSaveData {
     pub destroyed_actors: Array<ObjectReferenceDisc>,
 }
@@ -0,0 +1,60 @@
+use std::ops::{Add, Mul, Sub};
+
+use binrw::{BinRead, BinWrite};
+
+use crate::{parse_real, write_real};
+
+/// A 2D vector, stored as floats before Unreal Engine 5 and as doubles since.
+#[derive(Debug, Clone, Copy, Default, PartialEq, BinRead, BinWrite)]
+#[br(import(double_precision: bool))]
+#[bw(import(double_precision: bool))]
+pub struct Vector2d {
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub x: f64,
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub y: f64,
+}
+
+impl Vector2d {
+    pub fn new(x: f64, y: f64) -> Self {
+        Vector2d { x, y }
+    }
+
+    pub fn dot(self, other: Vector2d) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn distance(self, other: Vector2d) -> f64 {
+        (self - other).length()
+    }
+}
+
+impl Add for Vector2d {
+    type Output = Vector2d;
+
+    fn add(self, other: Vector2d) -> Vector2d {
+        Vector2d::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector2d {
+    type Output = Vector2d;
+
+    fn sub(self, other: Vector2d) -> Vector2d {
+        Vector2d::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f64> for Vector2d {
+    type Output = Vector2d;
+
+    fn mul(self, scale: f64) -> Vector2d {
+        Vector2d::new(self.x * scale, self.y * scale)
+    }
+}
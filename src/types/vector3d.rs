@@ -0,0 +1,90 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use binrw::{BinRead, BinWrite};
+
+use crate::{parse_real, write_real};
+
+/// A 3D vector, stored as floats before Unreal Engine 5 and as doubles since.
+/// Positions are in centimetres.
+#[derive(Debug, Clone, Copy, Default, PartialEq, BinRead, BinWrite)]
+#[br(import(double_precision: bool))]
+#[bw(import(double_precision: bool))]
+pub struct Vector3d {
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub x: f64,
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub y: f64,
+    #[br(parse_with = parse_real, args(double_precision))]
+    #[bw(write_with = write_real, args(double_precision))]
+    pub z: f64,
+}
+
+impl Vector3d {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Vector3d { x, y, z }
+    }
+
+    pub fn dot(self, other: Vector3d) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vector3d) -> Vector3d {
+        Vector3d::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn distance(self, other: Vector3d) -> f64 {
+        (self - other).length()
+    }
+
+    /// Returns the vector scaled to unit length, or the zero vector if it has no length.
+    pub fn normalized(self) -> Vector3d {
+        let length = self.length();
+        if length == 0.0 {
+            Vector3d::default()
+        } else {
+            self * (1.0 / length)
+        }
+    }
+}
+
+impl Add for Vector3d {
+    type Output = Vector3d;
+
+    fn add(self, other: Vector3d) -> Vector3d {
+        Vector3d::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vector3d {
+    type Output = Vector3d;
+
+    fn sub(self, other: Vector3d) -> Vector3d {
+        Vector3d::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for Vector3d {
+    type Output = Vector3d;
+
+    fn mul(self, scale: f64) -> Vector3d {
+        Vector3d::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
+
+impl Neg for Vector3d {
+    type Output = Vector3d;
+
+    fn neg(self) -> Vector3d {
+        Vector3d::new(-self.x, -self.y, -self.z)
+    }
+}
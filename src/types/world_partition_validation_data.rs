@@ -1,8 +1,8 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
 use super::{map::Map, string::Name, wp_grid_validation_data::WPGridValidationData};
 
-#[derive(BinRead)]
+#[derive(Debug, BinRead, BinWrite)]
 pub struct WorldPartitionValidationData {
     pub grids: Map<Name, WPGridValidationData>,
 }
@@ -1,8 +1,8 @@
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 
 use super::{map::Map, string::Name};
 
-#[derive(BinRead)]
+#[derive(Debug, BinRead, BinWrite)]
 pub struct WPGridValidationData {
     pub cell_size: i32,
     pub grid_hash: u32,